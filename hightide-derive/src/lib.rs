@@ -0,0 +1,230 @@
+//! Derive macros for the hightide crate.
+//!
+//! Don't use this crate directly, enable the `derive` feature of `hightide` instead.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, LitInt, LitStr};
+
+/// Derive `Responder` for a struct, serializing it as a JSON body
+///
+/// The struct must implement `serde::Serialize`. Use the `response` attribute on the struct
+/// to set the status code (default 200) and the content type (default `application/json`).
+///
+/// Fields marked with `#[response(header = "...")]` are also set as a response header, using
+/// their `Display` output as the value. The field is still serialized into the body, add
+/// `#[serde(skip)]` to leave it out. A value which isn't a valid header value, such as one
+/// containing CR or LF, makes `into_response` return an error.
+///
+/// ```
+/// use hightide::Responder;
+/// use serde::Serialize;
+/// use tide::StatusCode;
+///
+/// #[derive(Serialize, Responder)]
+/// #[response(status = 201, content_type = "application/vnd.api+json")]
+/// struct UserCreated {
+///     id: u32,
+///     #[serde(skip)]
+///     #[response(header = "X-Request-Id")]
+///     request_id: String,
+/// }
+///
+/// let resp = UserCreated { id: 42, request_id: "abc".into() }.into_response().unwrap();
+/// assert_eq!(resp.status(), StatusCode::Created);
+/// assert_eq!(resp["Content-Type"], "application/vnd.api+json");
+/// assert_eq!(resp["X-Request-Id"], "abc");
+///
+/// let bad = UserCreated { id: 42, request_id: "a\r\nSet-Cookie: evil=1".into() };
+/// assert!(bad.into_response().is_err());
+/// ```
+///
+/// The status must be one that `tide::StatusCode` supports, the content type must be a valid
+/// media type and header names must be valid header names, otherwise the derive fails to
+/// compile.
+/// ```compile_fail
+/// # use hightide::Responder;
+/// #[derive(serde::Serialize, Responder)]
+/// #[response(status = 299)]
+/// struct Odd {}
+/// ```
+/// ```compile_fail
+/// # use hightide::Responder;
+/// #[derive(serde::Serialize, Responder)]
+/// #[response(content_type = "not a mime type")]
+/// struct Odd {}
+/// ```
+/// ```compile_fail
+/// # use hightide::Responder;
+/// #[derive(serde::Serialize, Responder)]
+/// struct Odd {
+///     #[response(header = "X Foo")]
+///     foo: String,
+/// }
+/// ```
+#[proc_macro_derive(Responder, attributes(response))]
+pub fn derive_responder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut status = 200u16;
+    let mut content_type = None;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("response")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("status") {
+                let lit: LitInt = meta.value()?.parse()?;
+                status = lit.base10_parse()?;
+                if !KNOWN_STATUSES.contains(&status) {
+                    return Err(Error::new(
+                        lit.span(),
+                        "unknown status code, expected one supported by tide::StatusCode",
+                    ));
+                }
+                Ok(())
+            } else if meta.path.is_ident("content_type") {
+                let lit: LitStr = meta.value()?.parse()?;
+                content_type = Some(mime_literal(ascii_literal(lit)?)?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `status` or `content_type`"))
+            }
+        })?;
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "Responder can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut headers = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("response")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("header") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    let member = match &field.ident {
+                        Some(ident) => quote!(#ident),
+                        None => {
+                            let index = syn::Index::from(i);
+                            quote!(#index)
+                        }
+                    };
+                    headers.push((header_name_literal(lit)?, member));
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `header`"))
+                }
+            })?;
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let content_type = content_type.map(|ct| {
+        quote! {
+            resp = resp.raw_header("Content-Type", #ct);
+        }
+    });
+
+    let headers = headers.iter().map(|(header, member)| {
+        quote! {
+            resp = resp.headers([(#header, self.#member.to_string())])?;
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::hightide::Responder for #name #ty_generics #where_clause {
+            fn into_response(self) -> ::hightide::__private::tide::Result<::hightide::__private::tide::Response> {
+                use ::hightide::__private::tide as __tide;
+                use ::std::convert::TryFrom;
+
+                let status = __tide::StatusCode::try_from(#status)?;
+                let mut resp = ::hightide::Response::status(status).json(&self)?;
+                #content_type
+                #(#headers)*
+                Ok(resp.into_inner())
+            }
+        }
+    })
+}
+
+/// The status codes which `tide::StatusCode::try_from` accepts
+const KNOWN_STATUSES: &[u16] = &[
+    100, 101, 103, 200, 201, 202, 203, 204, 205, 206, 207, 226, 300, 301, 302, 303, 304, 307, 308,
+    400, 401, 402, 403, 404, 405, 406, 407, 408, 409, 410, 411, 412, 413, 414, 415, 416, 417, 418,
+    421, 422, 423, 424, 425, 426, 428, 429, 431, 451, 500, 501, 502, 503, 504, 505, 506, 507, 508,
+    510, 511,
+];
+
+/// Check the literal looks like a media type, `type/subtype` with optional `; name=value`
+/// parameters. Parameter values can be tokens or quoted strings.
+fn mime_literal(lit: LitStr) -> syn::Result<LitStr> {
+    let value = lit.value();
+    let mut parts = value.split(';');
+    let essence = parts.next().unwrap_or("").trim();
+    let valid_essence = match essence.split_once('/') {
+        Some((ty, subtype)) => is_token(ty) && is_token(subtype),
+        None => false,
+    };
+    let valid_params = parts.all(|param| match param.trim().split_once('=') {
+        Some((name, value)) => is_token(name) && (is_token(value) || is_quoted(value)),
+        None => false,
+    });
+
+    if valid_essence && valid_params {
+        Ok(lit)
+    } else {
+        Err(Error::new(
+            lit.span(),
+            "invalid content type, expected a media type like `application/json`",
+        ))
+    }
+}
+
+/// Check the literal is a valid header name, which must be a token
+fn header_name_literal(lit: LitStr) -> syn::Result<LitStr> {
+    if is_token(&lit.value()) {
+        Ok(lit)
+    } else {
+        Err(Error::new(
+            lit.span(),
+            "invalid header name, expected a token like `X-Request-Id`",
+        ))
+    }
+}
+
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+fn is_quoted(s: &str) -> bool {
+    s.len() >= 2
+        && s.starts_with('"')
+        && s.ends_with('"')
+        && !s[1..s.len() - 1].contains(|c: char| c == '"' || c.is_ascii_control())
+}
+
+fn ascii_literal(lit: LitStr) -> syn::Result<LitStr> {
+    if lit.value().is_ascii() {
+        Ok(lit)
+    } else {
+        Err(Error::new(
+            lit.span(),
+            "header names and values must be ASCII",
+        ))
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{checked_header_value, Response};
+use std::fmt;
+use tide::http::headers;
+
+/// An authentication challenge for the `WWW-Authenticate` header, used with
+/// `Response::www_authenticate`
+/// ```
+/// use hightide::Challenge;
+///
+/// assert_eq!(Challenge::basic("admin").to_string(), r#"Basic realm="admin""#);
+/// assert_eq!(
+///     Challenge::bearer("api").param("error", "invalid_token").to_string(),
+///     r#"Bearer realm="api", error="invalid_token""#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    scheme: String,
+    params: Vec<(String, String)>,
+}
+
+impl Challenge {
+    /// A challenge for any authentication scheme, with a realm
+    pub fn new(scheme: impl Into<String>, realm: impl Into<String>) -> Self {
+        Self {
+            scheme: scheme.into(),
+            params: vec![("realm".to_owned(), realm.into())],
+        }
+    }
+
+    /// A challenge for HTTP Basic authentication
+    pub fn basic(realm: impl Into<String>) -> Self {
+        Self::new("Basic", realm)
+    }
+
+    /// A challenge for Bearer token authentication
+    pub fn bearer(realm: impl Into<String>) -> Self {
+        Self::new("Bearer", realm)
+    }
+
+    /// Add another parameter, such as `error` for Bearer challenges. The value is quoted.
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((name.into(), value.into()));
+        self
+    }
+}
+
+impl fmt::Display for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.scheme)?;
+        for (i, (name, value)) in self.params.iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            write!(f, "{}{}=\"", sep, name)?;
+            for c in value.chars() {
+                if c == '"' || c == '\\' {
+                    f.write_str("\\")?;
+                }
+                write!(f, "{}", c)?;
+            }
+            f.write_str("\"")?;
+        }
+        Ok(())
+    }
+}
+
+impl Response {
+    /// Set the `WWW-Authenticate` header, from a `Challenge` or a string
+    ///
+    /// This should be sent with status Unauthorized (401). Returns an error if the challenge is
+    /// not a valid header value, for example if a parameter contains non-ASCII or control
+    /// characters such as CR or LF.
+    /// ```
+    /// use hightide::{Challenge, Response};
+    /// use tide::StatusCode;
+    ///
+    /// let resp = Response::status(StatusCode::Unauthorized)
+    ///     .www_authenticate(Challenge::basic("admin"))
+    ///     .unwrap()
+    ///     .into_inner();
+    /// assert_eq!(resp["WWW-Authenticate"], r#"Basic realm="admin""#);
+    ///
+    /// let challenge = Challenge::bearer("api").param("error_description", "bad\r\nX: 1");
+    /// assert!(Response::ok().www_authenticate(challenge).is_err());
+    /// assert!(Response::ok().www_authenticate(Challenge::basic("caf\u{e9}")).is_err());
+    /// ```
+    pub fn www_authenticate(mut self, challenge: impl fmt::Display) -> tide::Result<Self> {
+        let value = checked_header_value(&challenge.to_string())?;
+        self.inner.insert_header(headers::WWW_AUTHENTICATE, value);
+        Ok(self)
+    }
+}
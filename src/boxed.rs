@@ -0,0 +1,64 @@
+use crate::Responder;
+use tide::http;
+
+/// An object safe version of `Responder`, used by `BoxedResponder`
+///
+/// `Responder` consumes `self`, so its methods can't be called through a trait object. This
+/// trait takes `self: Box<Self>` instead and is implemented for every `Responder`, so you never
+/// need to implement it yourself.
+pub trait ResponderObject {
+    /// Convert the boxed responder into a response, see `Responder::into_response`
+    fn into_response_boxed(self: Box<Self>) -> tide::Result<tide::Response>;
+
+    /// Convert the boxed responder into a response for a request, see
+    /// `Responder::into_response_for`
+    fn into_response_for_boxed(
+        self: Box<Self>,
+        req: &http::Request,
+    ) -> tide::Result<tide::Response>;
+}
+
+impl<R: Responder> ResponderObject for R {
+    fn into_response_boxed(self: Box<Self>) -> tide::Result<tide::Response> {
+        (*self).into_response()
+    }
+
+    fn into_response_for_boxed(
+        self: Box<Self>,
+        req: &http::Request,
+    ) -> tide::Result<tide::Response> {
+        (*self).into_response_for(req)
+    }
+}
+
+/// A type erased responder, created with `Responder::boxed`
+///
+/// This allows different responder types to be stored together, or returned from different
+/// branches of an endpoint. It is `Send` so it can be returned from `wrap` endpoints.
+/// ```
+/// use hightide::{BoxedResponder, Json, Responder};
+/// use tide::StatusCode;
+///
+/// let responders: Vec<BoxedResponder> = vec![
+///     "hello".boxed(),
+///     StatusCode::NotFound.boxed(),
+///     Json(vec![1, 2, 3]).boxed(),
+/// ];
+///
+/// let statuses: Vec<_> = responders
+///     .into_iter()
+///     .map(|r| r.into_response().unwrap().status())
+///     .collect();
+/// assert_eq!(statuses, [StatusCode::Ok, StatusCode::NotFound, StatusCode::Ok]);
+/// ```
+pub type BoxedResponder = Box<dyn ResponderObject + Send>;
+
+impl Responder for BoxedResponder {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        self.into_response_boxed()
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        self.into_response_for_boxed(req)
+    }
+}
@@ -0,0 +1,124 @@
+use crate::Response;
+use std::fmt;
+use std::time::Duration;
+use tide::http::headers;
+
+/// A builder for the `Cache-Control` header, used with `Response::cache_control`
+///
+/// ```
+/// use hightide::{CacheControl, Response};
+/// use std::time::Duration;
+///
+/// let cc = CacheControl::new()
+///     .public()
+///     .max_age(Duration::from_millis(3_600_500))
+///     .immutable();
+/// assert_eq!(cc.to_string(), "public, max-age=3600, immutable");
+///
+/// let resp = Response::ok().cache_control(cc).into_inner();
+/// assert_eq!(resp["Cache-Control"], "public, max-age=3600, immutable");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheControl {
+    visibility: Option<&'static str>,
+    no_cache: bool,
+    no_store: bool,
+    max_age: Option<u64>,
+    immutable: bool,
+}
+
+impl CacheControl {
+    /// Create an empty set of directives
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set `max-age`, sub-second parts of the duration are truncated
+    pub fn max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age.as_secs());
+        self
+    }
+
+    /// Set `no-store`, the response must not be stored by any cache
+    pub fn no_store(mut self) -> Self {
+        self.no_store = true;
+        self
+    }
+
+    /// Set `no-cache`, caches must revalidate with the server before using the response
+    pub fn no_cache(mut self) -> Self {
+        self.no_cache = true;
+        self
+    }
+
+    /// Set `public`, the response may be stored by shared caches. Replaces `private`.
+    pub fn public(mut self) -> Self {
+        self.visibility = Some("public");
+        self
+    }
+
+    /// Set `private`, the response may only be stored by the client. Replaces `public`.
+    pub fn private(mut self) -> Self {
+        self.visibility = Some("private");
+        self
+    }
+
+    /// Set `immutable`, the response will not change while it is fresh
+    pub fn immutable(mut self) -> Self {
+        self.immutable = true;
+        self
+    }
+}
+
+impl fmt::Display for CacheControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut directives = Vec::new();
+        if let Some(visibility) = self.visibility {
+            directives.push(visibility.to_owned());
+        }
+        if self.no_cache {
+            directives.push("no-cache".to_owned());
+        }
+        if self.no_store {
+            directives.push("no-store".to_owned());
+        }
+        if let Some(max_age) = self.max_age {
+            directives.push(format!("max-age={}", max_age));
+        }
+        if self.immutable {
+            directives.push("immutable".to_owned());
+        }
+        f.write_str(&directives.join(", "))
+    }
+}
+
+impl Response {
+    /// Set the `Cache-Control` header
+    pub fn cache_control(mut self, cc: CacheControl) -> Self {
+        self.inner
+            .insert_header(headers::CACHE_CONTROL, cc.to_string());
+        self
+    }
+
+    /// Forbid caching of the response entirely
+    ///
+    /// This sets `Cache-Control: no-store, no-cache, must-revalidate`, `Pragma: no-cache` for
+    /// HTTP/1.0 caches and `Expires: 0`, replacing any existing values of those headers.
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// let resp = Response::ok().no_cache().into_inner();
+    /// assert_eq!(resp["Cache-Control"], "no-store, no-cache, must-revalidate");
+    /// assert_eq!(resp["Pragma"], "no-cache");
+    /// assert_eq!(resp["Expires"], "0");
+    /// ```
+    pub fn no_cache(mut self) -> Self {
+        self.inner.insert_header(
+            headers::CACHE_CONTROL,
+            "no-store, no-cache, must-revalidate",
+        );
+        self.inner.insert_header("Pragma", "no-cache");
+        self.inner.insert_header(headers::EXPIRES, "0");
+        self
+    }
+}
@@ -0,0 +1,73 @@
+use crate::Response;
+use async_compression::futures::bufread::{BrotliEncoder, GzipEncoder};
+use futures::AsyncReadExt;
+use tide::http::headers;
+use tide::Body;
+
+/// The compression algorithm used by `Response::compressed`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// gzip compression, `Content-Encoding: gzip`
+    Gzip,
+    /// Brotli compression, `Content-Encoding: br`
+    Brotli,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Brotli => "br",
+        }
+    }
+}
+
+impl Response {
+    /// Compress the body of the response (requires the `compression` feature)
+    ///
+    /// This sets the `Content-Encoding` header and updates `Content-Length` to the compressed
+    /// size. The body is read fully into memory to be compressed, so this is not suitable for
+    /// streaming bodies.
+    /// ```
+    /// use hightide::{Encoding, Response};
+    ///
+    /// # async_std::task::block_on(async {
+    /// let resp = Response::ok()
+    ///     .json(vec!["a"; 1000]).unwrap()
+    ///     .compressed(Encoding::Gzip).await.unwrap()
+    ///     .into_inner();
+    /// assert_eq!(resp["Content-Encoding"], "gzip");
+    /// assert_eq!(resp["Content-Type"], "application/json");
+    /// assert!(resp.len().unwrap() < 4000);
+    /// # });
+    /// ```
+    pub async fn compressed(mut self, encoding: Encoding) -> tide::Result<Self> {
+        let body = self.inner.take_body();
+        let mime = body.mime().clone();
+        let bytes = body.into_bytes().await?;
+
+        let mut compressed = Vec::new();
+        match encoding {
+            Encoding::Gzip => {
+                GzipEncoder::new(&bytes[..])
+                    .read_to_end(&mut compressed)
+                    .await?
+            }
+            Encoding::Brotli => {
+                BrotliEncoder::new(&bytes[..])
+                    .read_to_end(&mut compressed)
+                    .await?
+            }
+        };
+
+        let len = compressed.len();
+        let mut body = Body::from_bytes(compressed);
+        body.set_mime(mime);
+        self.inner.set_body(body);
+        self.inner
+            .insert_header(headers::CONTENT_ENCODING, encoding.name());
+        self.inner
+            .insert_header(headers::CONTENT_LENGTH, len.to_string());
+        Ok(self)
+    }
+}
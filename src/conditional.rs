@@ -0,0 +1,203 @@
+use crate::{Responder, Response};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tide::http::conditional::{ETag, IfModifiedSince, IfNoneMatch, LastModified};
+use tide::http::{self, headers, Method};
+use tide::StatusCode;
+
+impl Response {
+    /// Set a strong `ETag` header, the tag is given without quotes and is sent as `"tag"`
+    ///
+    /// Strong tags mean the body is byte for byte identical whenever the tag matches, use
+    /// `weak_etag` if the body only has equivalent meaning (sent as `W/"tag"`).
+    ///
+    /// Panics if the tag contains characters not allowed in an ETag, such as quotes or
+    /// non-ASCII characters
+    pub fn etag(self, tag: impl AsRef<str>) -> Self {
+        self.set_etag(ETag::new(valid_tag(tag.as_ref())))
+    }
+
+    /// Set a weak `ETag` header, the tag is given without quotes and is sent as `W/"tag"`
+    ///
+    /// Panics if the tag contains characters not allowed in an ETag, such as quotes or
+    /// non-ASCII characters
+    pub fn weak_etag(self, tag: impl AsRef<str>) -> Self {
+        self.set_etag(ETag::new_weak(valid_tag(tag.as_ref())))
+    }
+
+    fn set_etag(mut self, etag: ETag) -> Self {
+        etag.apply(&mut self.inner);
+        self
+    }
+
+    /// Set the `Last-Modified` header, formatted as an HTTP date (the IMF-fixdate format from
+    /// RFC 7231)
+    ///
+    /// HTTP dates can't represent times before the Unix epoch or after the year 9999, so those
+    /// times are clamped to the nearest date that can be sent.
+    /// ```
+    /// use hightide::Response;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let time = UNIX_EPOCH + Duration::from_secs(784111777);
+    /// let resp = Response::ok().last_modified(time).into_inner();
+    /// assert_eq!(resp["Last-Modified"], "Sun, 06 Nov 1994 08:49:37 GMT");
+    ///
+    /// let resp = Response::ok().last_modified(UNIX_EPOCH - Duration::from_secs(60)).into_inner();
+    /// assert_eq!(resp["Last-Modified"], "Thu, 01 Jan 1970 00:00:00 GMT");
+    /// ```
+    pub fn last_modified(mut self, time: SystemTime) -> Self {
+        LastModified::new(clamp_http_date(time)).apply(&mut self.inner);
+        self
+    }
+}
+
+/// Clamp a time to the range which can be formatted as an HTTP date, the formatting in
+/// `http_types` panics outside of this range
+pub(crate) fn clamp_http_date(time: SystemTime) -> SystemTime {
+    // 9999-12-31T23:59:59Z
+    let max = UNIX_EPOCH + Duration::from_secs(253_402_300_799);
+    time.clamp(UNIX_EPOCH, max)
+}
+
+fn valid_tag(tag: &str) -> String {
+    assert!(
+        tag.bytes().all(|c| c == 0x21 || (0x23..=0x7E).contains(&c)),
+        "invalid ETag"
+    );
+    tag.to_owned()
+}
+
+/// Compute a strong ETag from the body bytes, using a 64-bit FNV-1a hash
+fn hash_tag(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf29ce484222325u64, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x100000001b3)
+    });
+    format!("{:x}-{:016x}", bytes.len(), hash)
+}
+
+/// A wrapper which handles conditional GET requests using the `ETag` or `Last-Modified` of the
+/// inner response
+///
+/// If the request has an `If-None-Match` header matching the `ETag` then the response is
+/// replaced with Not Modified (304) and the body is dropped. The other headers are kept. Tags
+/// are compared using the weak comparison, as required for `If-None-Match`.
+///
+/// Requests without `If-None-Match` are checked using `If-Modified-Since` instead, a response
+/// which has not been modified since that date is also replaced with Not Modified. An invalid
+/// `If-Modified-Since` date is ignored.
+///
+/// The request is only available when returned from an endpoint wrapped with `wrap` or one of
+/// the other `wrap_*` functions, otherwise (for example when calling `into_response` directly)
+/// the inner response is always returned.
+/// ```
+/// use hightide::{Conditional, Responder, Response};
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+/// use tide::http::{Method, Request};
+/// use tide::StatusCode;
+///
+/// let mut req = Request::new(Method::Get, "http://example.com/");
+/// req.insert_header("If-None-Match", "\"v1\"");
+///
+/// let resp = Conditional::new(Response::ok().body("hello").etag("v1"))
+///     .into_response_for(&req)
+///     .unwrap();
+/// assert_eq!(resp.status(), StatusCode::NotModified);
+/// assert_eq!(resp["ETag"], "\"v1\"");
+///
+/// let resp = Conditional::new(Response::ok().body("hello").etag("v2"))
+///     .into_response_for(&req)
+///     .unwrap();
+/// assert_eq!(resp.status(), StatusCode::Ok);
+///
+/// let mut req = Request::new(Method::Get, "http://example.com/");
+/// req.insert_header("If-Modified-Since", "Sun, 06 Nov 1994 08:49:37 GMT");
+/// let modified = UNIX_EPOCH + Duration::from_secs(784111777);
+///
+/// let resp = Conditional::new(Response::ok().body("hello").last_modified(modified))
+///     .into_response_for(&req)
+///     .unwrap();
+/// assert_eq!(resp.status(), StatusCode::NotModified);
+///
+/// let resp = Conditional::new(Response::ok().body("hello").last_modified(SystemTime::now()))
+///     .into_response_for(&req)
+///     .unwrap();
+/// assert_eq!(resp.status(), StatusCode::Ok);
+/// ```
+pub struct Conditional<R: Responder>(R);
+
+impl<R: Responder> Conditional<R> {
+    /// Wrap a responder which sets its own `ETag` header
+    pub fn new(inner: R) -> Self {
+        Self(inner)
+    }
+}
+
+impl Conditional<Response> {
+    /// Create a response from the body bytes, with a strong `ETag` computed from a hash of the
+    /// bytes
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        let bytes = bytes.into();
+        let tag = hash_tag(&bytes);
+        Self(Response::ok().body(bytes).etag(tag))
+    }
+}
+
+impl<R: Responder> Responder for Conditional<R> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        self.0.into_response()
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        let mut resp = self.0.into_response_for(req)?;
+        if is_not_modified(req, &resp)? {
+            resp.set_status(StatusCode::NotModified);
+            resp.take_body();
+            resp.remove_header(headers::CONTENT_TYPE);
+        }
+        Ok(resp)
+    }
+}
+
+fn is_not_modified(req: &http::Request, resp: &tide::Response) -> tide::Result<bool> {
+    if !matches!(req.method(), Method::Get | Method::Head) || !resp.status().is_success() {
+        return Ok(false);
+    }
+
+    match IfNoneMatch::from_headers(req)? {
+        Some(if_none_match) => etag_matches(&if_none_match, resp),
+        None => not_modified_since(req, resp),
+    }
+}
+
+fn etag_matches(if_none_match: &IfNoneMatch, resp: &tide::Response) -> tide::Result<bool> {
+    let etag = match ETag::from_headers(resp)? {
+        Some(etag) => etag,
+        None => return Ok(false),
+    };
+
+    Ok(if_none_match.wildcard() || if_none_match.iter().any(|tag| weak_eq(tag, &etag)))
+}
+
+fn not_modified_since(req: &http::Request, resp: &tide::Response) -> tide::Result<bool> {
+    let since = match IfModifiedSince::from_headers(req) {
+        Ok(Some(since)) => since.modified(),
+        _ => return Ok(false),
+    };
+
+    let modified = match LastModified::from_headers(resp)? {
+        Some(modified) => modified.modified(),
+        None => return Ok(false),
+    };
+
+    Ok(modified <= since)
+}
+
+fn weak_eq(a: &ETag, b: &ETag) -> bool {
+    opaque_tag(a) == opaque_tag(b)
+}
+
+fn opaque_tag(etag: &ETag) -> &str {
+    match etag {
+        ETag::Strong(s) | ETag::Weak(s) => s,
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{checked_header_value, Response};
+use tide::http::Method;
+
+impl Response {
+    /// Set the `Access-Control-Allow-Origin` header
+    ///
+    /// These CORS helpers only set headers on this response, they don't answer preflight
+    /// `OPTIONS` requests - add an `options` route (or use a CORS middleware) for that.
+    ///
+    /// Returns an error if the origin is not a valid header value
+    /// ```
+    /// use hightide::Response;
+    /// use tide::http::Method;
+    ///
+    /// # fn main() -> tide::Result<()> {
+    /// let resp = Response::ok()
+    ///     .cors_allow_origin("https://example.com")?
+    ///     .cors_allow_methods(&[Method::Get, Method::Post])
+    ///     .cors_allow_headers(&["Content-Type", "X-Token"])?
+    ///     .into_inner();
+    /// assert_eq!(resp["Access-Control-Allow-Origin"], "https://example.com");
+    /// assert_eq!(resp["Access-Control-Allow-Methods"], "GET, POST");
+    /// assert_eq!(resp["Access-Control-Allow-Headers"], "Content-Type, X-Token");
+    ///
+    /// assert!(Response::ok().cors_allow_origin("https://caf\u{e9}.example").is_err());
+    /// assert!(Response::ok().cors_allow_headers(&["X-Token\r\nX-Bad: 1"]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cors_allow_origin(self, origin: impl AsRef<str>) -> tide::Result<Self> {
+        self.cors_header("Access-Control-Allow-Origin", origin.as_ref())
+    }
+
+    /// Set the `Access-Control-Allow-Methods` header
+    pub fn cors_allow_methods(mut self, methods: &[Method]) -> Self {
+        let methods = methods
+            .iter()
+            .map(|method| method.to_string())
+            .collect::<Vec<_>>();
+        self.inner
+            .insert_header("Access-Control-Allow-Methods", methods.join(", "));
+        self
+    }
+
+    /// Set the `Access-Control-Allow-Headers` header
+    ///
+    /// Returns an error if a header name is not a valid header value
+    pub fn cors_allow_headers(self, headers: &[&str]) -> tide::Result<Self> {
+        self.cors_header("Access-Control-Allow-Headers", &headers.join(", "))
+    }
+
+    fn cors_header(mut self, name: &'static str, value: &str) -> tide::Result<Self> {
+        let value = checked_header_value(value)?;
+        self.inner.insert_header(name, value);
+        Ok(self)
+    }
+}
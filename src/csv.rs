@@ -0,0 +1,116 @@
+use crate::stream::body_from_stream;
+use crate::{Responder, Response};
+use futures::{stream, StreamExt};
+use std::io;
+use std::sync::Mutex;
+use tide::convert::Serialize;
+use tide::http::Mime;
+
+/// A streaming CSV response body (requires the `csv` feature)
+///
+/// Records are written with the `csv` crate one at a time as the body is sent, so large exports
+/// aren't buffered in memory. The content type is `text/csv`, and `filename` adds a
+/// `Content-Disposition: attachment` header (see `Response::attachment`).
+///
+/// When the records are structs (or maps) a header row is written from the field names of the
+/// first record, records which are tuples or sequences have no names so no header row is
+/// written. Use `no_headers` to skip the header row for structs. If a record fails to
+/// serialize the body stops with the error, so the connection is closed.
+/// ```
+/// use hightide::{Csv, Responder};
+/// # #[derive(serde::Serialize)]
+/// # struct User { id: u32, name: &'static str }
+///
+/// # async_std::task::block_on(async {
+/// let users = vec![User { id: 1, name: "steve" }, User { id: 2, name: "sue" }];
+/// let mut resp = Csv::new(users).filename("users.csv").into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "text/csv");
+/// assert_eq!(resp["Content-Disposition"], "attachment; filename=\"users.csv\"");
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "id,name\n1,steve\n2,sue\n");
+///
+/// let users = vec![User { id: 1, name: "steve" }];
+/// let mut resp = Csv::new(users).no_headers().into_response().unwrap();
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "1,steve\n");
+/// # });
+/// ```
+pub struct Csv<I> {
+    records: I,
+    headers: bool,
+    filename: Option<String>,
+}
+
+impl<I> Csv<I>
+where
+    I: Iterator + Send + Sync + 'static,
+    I::Item: Serialize,
+{
+    /// Create the response from a collection of records
+    pub fn new(records: impl IntoIterator<IntoIter = I>) -> Self {
+        Self {
+            records: records.into_iter(),
+            headers: true,
+            filename: None,
+        }
+    }
+
+    /// Don't write a header row
+    pub fn no_headers(mut self) -> Self {
+        self.headers = false;
+        self
+    }
+
+    /// Send the body as a download with this filename
+    pub fn filename(mut self, name: impl Into<String>) -> Self {
+        self.filename = Some(name.into());
+        self
+    }
+}
+
+impl<I> Responder for Csv<I>
+where
+    I: Iterator + Send + Sync + 'static,
+    I::Item: Serialize,
+{
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let writer = csv::WriterBuilder::new()
+            .has_headers(self.headers)
+            .from_writer(Chunk::default());
+
+        let chunks = stream::iter(self.records).scan(writer, |writer, record| {
+            let chunk = write_record(writer, record);
+            futures::future::ready(Some(chunk))
+        });
+
+        let mut body = body_from_stream(chunks);
+        body.set_mime(Mime::from("text/csv"));
+        let resp = Response::from(tide::Response::from(body));
+        let resp = match self.filename {
+            Some(name) => resp.attachment(name),
+            None => resp,
+        };
+        Ok(resp.into_inner())
+    }
+}
+
+/// The output buffer of the csv writer, which only gives shared access to its writer so the
+/// bytes are taken out through a mutex
+#[derive(Default)]
+struct Chunk(Mutex<Vec<u8>>);
+
+impl io::Write for Chunk {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.get_mut().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Write one record and take the bytes written so far
+fn write_record(writer: &mut csv::Writer<Chunk>, record: impl Serialize) -> io::Result<Vec<u8>> {
+    writer.serialize(record).map_err(io::Error::other)?;
+    writer.flush()?;
+    Ok(std::mem::take(&mut *writer.get_ref().0.lock().unwrap()))
+}
@@ -0,0 +1,409 @@
+use crate::conditional::clamp_http_date;
+use crate::request_id::HighRequestId;
+use crate::{wrap, ErrorFormatter, High};
+use async_trait::async_trait;
+use hyperx::header::HttpDate;
+use std::net::SocketAddr;
+use std::time::{Instant, SystemTime};
+use tide::http;
+use tide::http::headers::{self, HeaderName};
+use tide::{Body, Endpoint, Request};
+
+/// Decorators which add behaviour to an endpoint, so they can be stacked
+///
+/// This is implemented for every `tide::Endpoint`, including the `wrap` functions and the
+/// decorators themselves. Each `wrap_*` function is a shortcut for `wrap` followed by one
+/// decorator, use these methods to combine several. The decorators run in the order they are
+/// applied from the outside in, so the last one applied sees the request first and the
+/// response last.
+/// ```
+/// use hightide::{wrap, EndpointExt};
+/// use log::Level;
+///
+/// # async_std::task::block_on(async {
+/// let mut app = tide::new();
+/// app.at("/").get(
+///     wrap(|_| async { "hello" })
+///         .timed()
+///         .with_request_id()
+///         .logged(Level::Info),
+/// );
+///
+/// let req = tide::http::Request::new(tide::http::Method::Get, "http://localhost/");
+/// let resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert!(resp["Server-Timing"].as_str().starts_with("app;dur="));
+/// assert_eq!(resp["X-Request-Id"].as_str().len(), 36);
+/// # });
+/// ```
+pub trait EndpointExt<State>: Endpoint<State> + Sized
+where
+    State: Clone + Send + Sync + 'static,
+{
+    /// Add a `Server-Timing` header with how long the endpoint took, see `wrap_timed`
+    fn timed(self) -> HighTimed<Self> {
+        HighTimed(self)
+    }
+
+    /// Log each response, see `wrap_logged`
+    fn logged(self, level: log::Level) -> HighLogged<Self> {
+        HighLogged::new(level, self)
+    }
+
+    /// Propagate the request id, see `wrap_request_id`
+    fn with_request_id(self) -> HighRequestId<Self> {
+        HighRequestId::new(self)
+    }
+
+    /// Answer HEAD requests without a body, see `wrap_head`
+    fn head(self) -> HighHead<Self> {
+        HighHead(self)
+    }
+
+    /// Render errors with `formatter`, see `wrap_with_errors`
+    fn with_errors<E: ErrorFormatter>(self, formatter: E) -> HighErrors<E, Self> {
+        HighErrors {
+            formatter,
+            inner: self,
+        }
+    }
+}
+
+impl<State, T> EndpointExt<State> for T
+where
+    State: Clone + Send + Sync + 'static,
+    T: Endpoint<State>,
+{
+}
+
+/// Times an endpoint, see `wrap_timed`
+pub struct HighTimed<E>(E);
+
+/// Wrap an endpoint to allow it to return the Responder types, adding a `Server-Timing` header
+/// with how long it took
+///
+/// The header is `Server-Timing: app;dur=<ms>`, added to any `Server-Timing` values the endpoint
+/// set itself. Only the endpoint future and building the response are timed, a streaming body
+/// is sent after the measurement so it isn't included.
+/// ```
+/// use hightide::wrap_timed;
+///
+/// # async_std::task::block_on(async {
+/// let mut app = tide::new();
+/// app.at("/").get(wrap_timed(|_| async { "hello" }));
+///
+/// let req = tide::http::Request::new(tide::http::Method::Get, "http://localhost/");
+/// let resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert!(resp["Server-Timing"].as_str().starts_with("app;dur="));
+/// # });
+/// ```
+pub fn wrap_timed<F>(f: F) -> HighTimed<High<F>> {
+    HighTimed(wrap(f))
+}
+
+#[async_trait]
+impl<State, E> Endpoint<State> for HighTimed<E>
+where
+    State: Clone + Send + Sync + 'static,
+    E: Endpoint<State>,
+{
+    async fn call(&self, req: Request<State>) -> tide::Result<tide::Response> {
+        let start = Instant::now();
+        let mut resp = self.0.call(req).await?;
+        let elapsed = start.elapsed();
+
+        let timing = format!("app;dur={:.1}", elapsed.as_secs_f64() * 1000.0);
+        resp.append_header("Server-Timing", timing);
+        Ok(resp)
+    }
+}
+
+/// Answers HEAD requests, see `wrap_head`
+pub struct HighHead<E>(E);
+
+/// Wrap an endpoint to allow it to return the Responder types, and to answer HEAD requests
+/// with the length of a streaming body
+///
+/// Tide routes HEAD requests to the GET endpoint when there's no HEAD endpoint, and the server
+/// doesn't send the body of a HEAD response, so plain `wrap` already answers HEAD correctly
+/// when the body has a known length. A streaming body has no known length though, so its HEAD
+/// response has no `Content-Length`. This wrapper reads a streaming body to the end to measure
+/// it and then removes the body, so the HEAD response has the same `Content-Length` as GET. The whole response is still computed, which may be wasteful for
+/// expensive endpoints - add a separate `head` route for those.
+/// ```
+/// use futures::stream;
+/// use hightide::{wrap_head, StreamBody};
+/// use std::io;
+/// use tide::http::{Method, Request};
+///
+/// # async_std::task::block_on(async {
+/// let mut app = tide::new();
+/// app.at("/").get(wrap_head(|_| async {
+///     StreamBody::new(stream::iter(vec![Ok::<_, io::Error>(b"hello".to_vec())]))
+/// }));
+///
+/// let req = Request::new(Method::Head, "http://localhost/");
+/// let mut resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp["Content-Length"], "5");
+/// assert_eq!(resp.len(), Some(5));
+/// assert_eq!(resp.body_string().await.unwrap(), "");
+///
+/// let req = Request::new(Method::Get, "http://localhost/");
+/// let mut resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp.body_string().await.unwrap(), "hello");
+/// # });
+/// ```
+pub fn wrap_head<F>(f: F) -> HighHead<High<F>> {
+    HighHead(wrap(f))
+}
+
+#[async_trait]
+impl<State, E> Endpoint<State> for HighHead<E>
+where
+    State: Clone + Send + Sync + 'static,
+    E: Endpoint<State>,
+{
+    async fn call(&self, req: Request<State>) -> tide::Result<tide::Response> {
+        let head = req.method() == http::Method::Head;
+        let mut resp = self.0.call(req).await?;
+
+        if !head {
+            return Ok(resp);
+        }
+        if resp.header(headers::CONTENT_LENGTH).is_none() && resp.len().is_none() {
+            let body = resp.take_body();
+            let mime = body.mime().clone();
+            let mut body = Body::from_bytes(body.into_bytes().await?);
+            body.set_mime(mime);
+            resp.set_body(body);
+        }
+        clear_body(&mut resp);
+        Ok(resp)
+    }
+}
+
+/// Remove the body of a response to a HEAD request, keeping all the headers and the length
+///
+/// The length comes from the `Content-Length` header, or the length of the body if the header
+/// wasn't set. The body is replaced with one that has the same length but no bytes, because the
+/// server sets `Content-Length` from the length of the body and (for HEAD requests) doesn't send
+/// the body. For any other request the client would wait for bytes that never arrive, which is
+/// why this is only used by `HighHead`.
+fn clear_body(resp: &mut tide::Response) {
+    let len = resp
+        .header(headers::CONTENT_LENGTH)
+        .and_then(|values| values.as_str().parse::<usize>().ok())
+        .or_else(|| resp.len());
+
+    let mime = resp.take_body().mime().clone();
+    let mut body = Body::from_reader(futures::io::empty(), len);
+    body.set_mime(mime);
+    resp.set_body(body);
+    if let Some(len) = len {
+        resp.insert_header(headers::CONTENT_LENGTH, len.to_string());
+    }
+}
+
+/// Formats the errors of an endpoint, see `wrap_with_errors`
+pub struct HighErrors<E, T> {
+    formatter: E,
+    inner: T,
+}
+
+/// Wrap an endpoint to allow it to return the Responder types, rendering any error with
+/// `formatter`
+///
+/// This covers errors returned by the endpoint (for example from `tide::Result`) and errors
+/// from converting the responder into a response. See `JsonErrors` for an example.
+pub fn wrap_with_errors<E, F>(formatter: E, f: F) -> HighErrors<E, High<F>> {
+    HighErrors {
+        formatter,
+        inner: wrap(f),
+    }
+}
+
+#[async_trait]
+impl<State, E, T> Endpoint<State> for HighErrors<E, T>
+where
+    State: Clone + Send + Sync + 'static,
+    E: ErrorFormatter,
+    T: Endpoint<State>,
+{
+    async fn call(&self, req: Request<State>) -> tide::Result<tide::Response> {
+        match self.inner.call(req).await {
+            Ok(resp) => Ok(resp),
+            Err(err) => Ok(self.formatter.format(err)),
+        }
+    }
+}
+
+/// Logs the responses of an endpoint, see `wrap_logged`
+pub struct HighLogged<E> {
+    level: log::Level,
+    combined: bool,
+    inner: E,
+}
+
+/// Wrap an endpoint to allow it to return the Responder types, logging each response with the
+/// `log` crate at `level`
+///
+/// Each line is in the Common Log Format, `<remote> - - [<time>] "<method> <path> <version>"
+/// <status> <size>`, for example `127.0.0.1 - - [06/Nov/1994:08:49:37 +0000] "GET /users
+/// HTTP/1.1" 200 512`. Use `combined` to add the `Referer` and `User-Agent` headers for the
+/// Combined Log Format. Missing fields are logged as `-`, and the time is always in UTC. Errors
+/// are logged with their status. The line is logged after the endpoint returns but before the
+/// body is sent, so the size is `-` for streaming bodies whose length isn't known yet and the
+/// time spent streaming isn't included.
+/// ```
+/// use hightide::wrap_logged;
+/// use log::{Level, LevelFilter, Log, Metadata, Record};
+/// use std::sync::Mutex;
+/// use tide::http::{Method, Request, Version};
+///
+/// static LINES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+///
+/// // keeps the lines from hightide, leaving out the ones from tide's own logging
+/// struct Capture;
+/// impl Log for Capture {
+///     fn enabled(&self, metadata: &Metadata) -> bool {
+///         metadata.target().starts_with("hightide")
+///     }
+///     fn log(&self, record: &Record) {
+///         if self.enabled(record.metadata()) {
+///             LINES.lock().unwrap().push(record.args().to_string());
+///         }
+///     }
+///     fn flush(&self) {}
+/// }
+///
+/// /// Split a line around the time, which changes on every run
+/// fn without_time(line: &str) -> (&str, &str) {
+///     let (start, rest) = line.split_once(" [").unwrap();
+///     let (time, end) = rest.split_once("] ").unwrap();
+///     assert!(time.ends_with(" +0000"));
+///     (start, end)
+/// }
+///
+/// # async_std::task::block_on(async {
+/// log::set_logger(&Capture).unwrap();
+/// log::set_max_level(LevelFilter::Info);
+///
+/// let mut app = tide::new();
+/// app.at("/").get(wrap_logged(Level::Info, |_| async { "hello" }));
+/// app.at("/combined").get(wrap_logged(Level::Info, |_| async { "hello" }).combined());
+///
+/// let mut req = Request::new(Method::Get, "http://localhost/?page=2");
+/// req.set_peer_addr(Some("127.0.0.1:5000"));
+/// req.set_version(Some(Version::Http1_1));
+/// let _: tide::http::Response = app.respond(req).await.unwrap();
+/// let line = LINES.lock().unwrap().pop().unwrap();
+/// assert_eq!(without_time(&line), ("127.0.0.1 - -", r#""GET /?page=2 HTTP/1.1" 200 5"#));
+///
+/// let mut req = Request::new(Method::Get, "http://localhost/combined");
+/// req.insert_header("User-Agent", "curl/8.0");
+/// let _: tide::http::Response = app.respond(req).await.unwrap();
+/// let line = LINES.lock().unwrap().pop().unwrap();
+/// assert_eq!(
+///     without_time(&line),
+///     ("- - -", r#""GET /combined HTTP/1.1" 200 5 "-" "curl/8.0""#)
+/// );
+/// # });
+/// ```
+pub fn wrap_logged<F>(level: log::Level, f: F) -> HighLogged<High<F>> {
+    HighLogged::new(level, wrap(f))
+}
+
+impl<E> HighLogged<E> {
+    pub(crate) fn new(level: log::Level, inner: E) -> Self {
+        Self {
+            level,
+            combined: false,
+            inner,
+        }
+    }
+
+    /// Log in the Combined Log Format, adding the `Referer` and `User-Agent` headers
+    pub fn combined(mut self) -> Self {
+        self.combined = true;
+        self
+    }
+}
+
+#[async_trait]
+impl<State, E> Endpoint<State> for HighLogged<E>
+where
+    State: Clone + Send + Sync + 'static,
+    E: Endpoint<State>,
+{
+    async fn call(&self, req: Request<State>) -> tide::Result<tide::Response> {
+        let remote = match req.remote() {
+            Some(remote) => match remote.parse::<SocketAddr>() {
+                Ok(addr) => addr.ip().to_string(),
+                Err(_) => remote.to_owned(),
+            },
+            None => "-".to_owned(),
+        };
+        let mut target = req.url().path().to_owned();
+        if let Some(query) = req.url().query() {
+            target.push('?');
+            target.push_str(query);
+        }
+        // the server only speaks HTTP/1.x, so assume 1.1 when the version wasn't recorded
+        let request_line = format!(
+            "{} {} {}",
+            req.method(),
+            target,
+            req.version().unwrap_or(http::Version::Http1_1)
+        );
+        let extra = if self.combined {
+            format!(
+                " \"{}\" \"{}\"",
+                header_field(&req, headers::REFERER),
+                header_field(&req, headers::USER_AGENT)
+            )
+        } else {
+            String::new()
+        };
+
+        let resp = self.inner.call(req).await;
+        let (status, size) = match &resp {
+            Ok(resp) => (resp.status(), resp.len().map(|len| len.to_string())),
+            Err(err) => (err.status(), None),
+        };
+        log::log!(
+            self.level,
+            "{} - - [{}] \"{}\" {} {}{}",
+            remote,
+            log_time(SystemTime::now()),
+            escape_quotes(&request_line),
+            status as u16,
+            size.as_deref().unwrap_or("-"),
+            extra
+        );
+        resp
+    }
+}
+
+/// A request header for the log line, escaped for quoting, or `-` if it's missing
+fn header_field<State>(req: &Request<State>, name: HeaderName) -> String {
+    match req.header(name) {
+        Some(values) => escape_quotes(values.last().as_str()),
+        None => "-".to_owned(),
+    }
+}
+
+fn escape_quotes(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Format a time for the log line, like `06/Nov/1994:08:49:37 +0000`
+///
+/// This rearranges the HTTP date format (`Sun, 06 Nov 1994 08:49:37 GMT`), which has the same
+/// fields in a fixed layout.
+fn log_time(time: SystemTime) -> String {
+    let date = HttpDate::from(clamp_http_date(time)).to_string();
+    let fields = date.split(' ').collect::<Vec<_>>();
+    match fields[..] {
+        [_, day, month, year, time, _] => format!("{}/{}/{}:{} +0000", day, month, year, time),
+        _ => "-".to_owned(),
+    }
+}
@@ -0,0 +1,64 @@
+use crate::Response;
+use std::fmt::Write;
+
+impl Response {
+    /// Set `Content-Disposition: attachment` with a filename, prompting the browser to download
+    /// the response
+    ///
+    /// Filenames with non-ASCII characters are sent using the `filename*=UTF-8''...` form from
+    /// RFC 5987, along with an ASCII-only `filename` for older clients.
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// let resp = Response::ok().attachment("report.csv").into_inner();
+    /// assert_eq!(resp["Content-Disposition"], "attachment; filename=\"report.csv\"");
+    ///
+    /// let resp = Response::ok().attachment("résumé.pdf").into_inner();
+    /// assert_eq!(
+    ///     resp["Content-Disposition"],
+    ///     "attachment; filename=\"r_sum_.pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf"
+    /// );
+    /// ```
+    pub fn attachment(self, filename: impl AsRef<str>) -> Self {
+        self.disposition("attachment", filename.as_ref())
+    }
+
+    /// Set `Content-Disposition: inline` with a filename, the browser displays the response but
+    /// uses the filename if it is saved
+    pub fn inline(self, filename: impl AsRef<str>) -> Self {
+        self.disposition("inline", filename.as_ref())
+    }
+
+    fn disposition(mut self, kind: &str, filename: &str) -> Self {
+        self.inner
+            .insert_header("Content-Disposition", content_disposition(kind, filename));
+        self
+    }
+}
+
+fn content_disposition(kind: &str, filename: &str) -> String {
+    let mut value = format!("{}; filename=\"", kind);
+    for c in filename.chars() {
+        match c {
+            '"' | '\\' => {
+                value.push('\\');
+                value.push(c);
+            }
+            ' '..='~' => value.push(c),
+            _ => value.push('_'),
+        }
+    }
+    value.push('"');
+
+    if !filename.bytes().all(|b| (b' '..=b'~').contains(&b)) {
+        value.push_str("; filename*=UTF-8''");
+        for b in filename.bytes() {
+            if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+                value.push(b as char);
+            } else {
+                write!(value, "%{:02X}", b).unwrap();
+            }
+        }
+    }
+    value
+}
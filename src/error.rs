@@ -0,0 +1,213 @@
+use crate::Responder;
+use std::convert::Infallible;
+use tide::convert::json;
+use tide::http;
+use tide::StatusCode;
+
+/// An error type that renders its own response
+///
+/// Endpoints can return `Result<R, E>` where `R: Responder` and `E: ResponderError`, the `Err`
+/// case is converted using this trait instead of going through tide's error handling.
+///
+/// The status code comes from `status`, which defaults to Internal Server Error (500). The body
+/// comes from `into_error_response`, which defaults to an empty body.
+/// ```
+/// use hightide::{Json, Responder, ResponderError};
+/// use tide::convert::json;
+/// use tide::{Request, StatusCode};
+///
+/// enum ApiError {
+///     NotFound,
+///     Invalid(String),
+/// }
+///
+/// impl ResponderError for ApiError {
+///     fn status(&self) -> StatusCode {
+///         match self {
+///             ApiError::NotFound => StatusCode::NotFound,
+///             ApiError::Invalid(_) => StatusCode::BadRequest,
+///         }
+///     }
+///
+///     fn into_error_response(self) -> tide::Result<tide::Response> {
+///         let message = match self {
+///             ApiError::NotFound => "not found".to_owned(),
+///             ApiError::Invalid(reason) => reason,
+///         };
+///         Json(json!({ "error": message })).into_response()
+///     }
+/// }
+///
+/// fn get_user(_: Request<()>) -> Result<Json<Vec<u32>>, ApiError> {
+///     Err(ApiError::Invalid("bad user id".to_owned()))
+/// }
+///
+/// let err: Result<Json<Vec<u32>>, ApiError> = Err(ApiError::NotFound);
+/// let resp = err.into_response().unwrap();
+/// assert_eq!(resp.status(), StatusCode::NotFound);
+/// assert_eq!(resp["Content-Type"], "application/json");
+/// ```
+///
+/// There is no implementation for `Result<R, E>` with any `E: std::error::Error`, it would
+/// overlap with `tide::Result<R>` and with the implementation above. Instead either use `?` in
+/// an endpoint returning `tide::Result<R>`, which converts the error into a `tide::Error` with
+/// status 500, or add an empty implementation of this trait to get a 500 response (overriding
+/// `status` to customize it).
+/// ```
+/// use hightide::{Responder, ResponderError};
+/// use std::fmt;
+/// use tide::StatusCode;
+///
+/// #[derive(Debug)]
+/// struct DbError;
+///
+/// impl fmt::Display for DbError {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         f.write_str("database unavailable")
+///     }
+/// }
+///
+/// impl std::error::Error for DbError {}
+///
+/// fn load() -> Result<String, DbError> {
+///     Err(DbError)
+/// }
+///
+/// // convert into a `tide::Error` with `?`
+/// fn with_question_mark() -> tide::Result<String> {
+///     Ok(load()?)
+/// }
+/// let err = with_question_mark().into_response().unwrap_err();
+/// assert_eq!(err.status(), StatusCode::InternalServerError);
+///
+/// // or render the error as a response directly
+/// impl ResponderError for DbError {}
+/// let resp = load().into_response().unwrap();
+/// assert_eq!(resp.status(), StatusCode::InternalServerError);
+/// ```
+pub trait ResponderError {
+    /// The status code of the error response
+    fn status(&self) -> StatusCode {
+        StatusCode::InternalServerError
+    }
+
+    /// Convert the error into a response, the status code is always replaced by `status`
+    fn into_error_response(self) -> tide::Result<tide::Response>
+    where
+        Self: Sized,
+    {
+        Ok(tide::Response::new(StatusCode::Ok))
+    }
+}
+
+/// Can never be constructed, this allows `Result<R, Infallible>` to be returned from endpoints
+impl ResponderError for Infallible {
+    fn status(&self) -> StatusCode {
+        match *self {}
+    }
+}
+
+/// Allows `Result<R, StatusCode>` to be returned from endpoints, the `Err` case is an empty
+/// response with that status and no body
+/// ```
+/// use hightide::Responder;
+/// use tide::StatusCode;
+///
+/// fn check_access(admin: bool) -> Result<&'static str, StatusCode> {
+///     if admin {
+///         Ok("welcome")
+///     } else {
+///         Err(StatusCode::Forbidden)
+///     }
+/// }
+///
+/// let resp = check_access(false).into_response().unwrap();
+/// assert_eq!(resp.status(), StatusCode::Forbidden);
+/// assert!(resp.is_empty().unwrap());
+/// ```
+impl ResponderError for StatusCode {
+    fn status(&self) -> StatusCode {
+        *self
+    }
+}
+
+fn error_response(err: impl ResponderError) -> tide::Result<tide::Response> {
+    let status = err.status();
+    let mut resp = err.into_error_response()?;
+    resp.set_status(status);
+    Ok(resp)
+}
+
+impl<R: Responder, E: ResponderError> Responder for Result<R, E> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        match self {
+            Ok(r) => r.into_response(),
+            Err(e) => error_response(e),
+        }
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        match self {
+            Ok(r) => r.into_response_for(req),
+            Err(e) => error_response(e),
+        }
+    }
+}
+
+/// Renders errors returned from endpoints, used with `wrap_with_errors`
+///
+/// This is implemented for closures taking a `tide::Error`, and by `JsonErrors` which is a
+/// reasonable default.
+pub trait ErrorFormatter: Send + Sync + 'static {
+    /// Build the response for an error
+    fn format(&self, err: tide::Error) -> tide::Response;
+}
+
+impl<F> ErrorFormatter for F
+where
+    F: Fn(tide::Error) -> tide::Response + Send + Sync + 'static,
+{
+    fn format(&self, err: tide::Error) -> tide::Response {
+        self(err)
+    }
+}
+
+/// An `ErrorFormatter` which renders errors as a JSON object with the status code and a message
+///
+/// The body is `{"status": 404, "message": "..."}`. For client errors (4xx) the message is the
+/// error's message, for server errors (5xx) it is the standard reason phrase for the status so
+/// internal details aren't leaked to clients.
+/// ```
+/// use hightide::{wrap_with_errors, JsonErrors};
+/// use tide::StatusCode;
+///
+/// # async_std::task::block_on(async {
+/// let mut app = tide::new();
+/// app.at("/").get(wrap_with_errors(JsonErrors, |_| async {
+///     Err::<String, _>(tide::Error::from_str(StatusCode::BadRequest, "missing id"))
+/// }));
+///
+/// let req = tide::http::Request::new(tide::http::Method::Get, "http://localhost/");
+/// let mut resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp.status(), StatusCode::BadRequest);
+/// assert_eq!(resp["Content-Type"], "application/json");
+/// assert_eq!(resp.body_string().await.unwrap(), r#"{"message":"missing id","status":400}"#);
+/// # });
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonErrors;
+
+impl ErrorFormatter for JsonErrors {
+    fn format(&self, err: tide::Error) -> tide::Response {
+        let status = err.status();
+        let message = if status.is_server_error() {
+            status.canonical_reason().to_owned()
+        } else {
+            err.to_string()
+        };
+
+        let mut resp = tide::Response::new(status);
+        resp.set_body(json!({ "status": status as u16, "message": message }));
+        resp
+    }
+}
@@ -0,0 +1,310 @@
+use crate::conditional::clamp_http_date;
+use crate::Responder;
+use async_std::fs;
+use async_std::io::{BufReader, Read, Seek, SeekFrom};
+use std::fs::Metadata;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+use tide::http::conditional::LastModified;
+use tide::http::{self, headers, mime, Method, Mime};
+use tide::{Body, StatusCode};
+
+/// A file from disk, streamed as the response body
+///
+/// The content type is detected from the file extension, using the `mime_guess` crate when the
+/// `mime_guess` feature is enabled (otherwise only a few common types are detected). The
+/// `Content-Length` and `Last-Modified` headers are set from the file metadata.
+///
+/// When returned from an endpoint wrapped with `wrap` (or another `wrap_*` function), GET
+/// requests with a `Range` header get just the requested bytes with status Partial Content
+/// (206), or Range Not Satisfiable (416) if the range is outside the file. Only single ranges are supported, a request for multiple
+/// ranges gets the whole file.
+/// ```no_run
+/// use hightide::{File, Responder};
+/// use tide::Request;
+///
+/// async fn logo(_: Request<()>) -> tide::Result<impl Responder> {
+///     File::open("static/logo.png").await
+/// }
+/// ```
+pub struct File {
+    file: fs::File,
+    path: PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl File {
+    /// Open the file at `path`
+    ///
+    /// Returns an error with status Not Found (404) if the file doesn't exist, and an error if the
+    /// path is a directory.
+    /// ```
+    /// use hightide::{File, Responder};
+    /// use tide::StatusCode;
+    ///
+    /// # async_std::task::block_on(async {
+    /// let err = File::open("no/such/file.txt").await.err().unwrap();
+    /// assert_eq!(err.status(), StatusCode::NotFound);
+    ///
+    /// let resp = File::open("README.md").await.unwrap().into_response().unwrap();
+    /// assert!(resp.header("Content-Length").is_some());
+    /// assert!(resp.header("Last-Modified").is_some());
+    ///
+    /// assert!(File::open("src").await.is_err());
+    /// # });
+    /// ```
+    pub async fn open(path: impl AsRef<Path>) -> tide::Result<Self> {
+        let path = path.as_ref();
+        let file = fs::File::open(path).await.map_err(not_found)?;
+        let metadata = file.metadata().await?;
+        Self::from_metadata(file, path.to_owned(), metadata)
+    }
+
+    /// Open the file without going through the async runtime, for `Responder` implementations
+    /// which can't await
+    fn open_blocking(path: PathBuf) -> tide::Result<Self> {
+        let file = std::fs::File::open(&path).map_err(not_found)?;
+        let metadata = file.metadata()?;
+        Self::from_metadata(fs::File::from(file), path, metadata)
+    }
+
+    fn from_metadata(file: fs::File, path: PathBuf, metadata: Metadata) -> tide::Result<Self> {
+        if metadata.is_dir() {
+            return Err(tide::Error::from_str(
+                StatusCode::InternalServerError,
+                format!("{} is a directory", path.display()),
+            ));
+        }
+
+        Ok(Self {
+            file,
+            path,
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+fn not_found(err: io::Error) -> tide::Error {
+    match err.kind() {
+        io::ErrorKind::NotFound => tide::Error::new(StatusCode::NotFound, err),
+        _ => err.into(),
+    }
+}
+
+#[cfg(feature = "mime_guess")]
+fn guess_mime(path: &Path) -> Mime {
+    mime_guess::from_path(path)
+        .first_raw()
+        .and_then(|mime| mime.parse().ok())
+        .unwrap_or(mime::BYTE_STREAM)
+}
+
+#[cfg(not(feature = "mime_guess"))]
+fn guess_mime(path: &Path) -> Mime {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(Mime::from_extension)
+        .unwrap_or(mime::BYTE_STREAM)
+}
+
+impl File {
+    fn response(self, range: Option<(u64, u64)>) -> tide::Result<tide::Response> {
+        let mime = guess_mime(&self.path);
+        let mut resp = match range {
+            None => {
+                let len = self.len as usize;
+                let body = Body::from_reader(BufReader::new(self.file), Some(len));
+                tide::Response::from(body)
+            }
+            Some((start, end)) => {
+                let len = (end - start + 1) as usize;
+                let reader = RangeReader {
+                    file: self.file,
+                    start,
+                    remaining: len as u64,
+                    seeked: false,
+                };
+                let mut resp = tide::Response::new(StatusCode::PartialContent);
+                resp.set_body(Body::from_reader(BufReader::new(reader), Some(len)));
+                resp.insert_header(
+                    headers::CONTENT_RANGE,
+                    format!("bytes {}-{}/{}", start, end, self.len),
+                );
+                resp
+            }
+        };
+        resp.set_content_type(mime);
+
+        let len = resp.len().unwrap_or_default();
+        resp.insert_header(headers::CONTENT_LENGTH, len.to_string());
+        resp.insert_header(headers::ACCEPT_RANGES, "bytes");
+        if let Some(modified) = self.modified {
+            LastModified::new(clamp_http_date(modified)).apply(&mut resp);
+        }
+        Ok(resp)
+    }
+}
+
+impl Responder for File {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        self.response(None)
+    }
+
+    /// ```
+    /// use hightide::{File, Responder};
+    /// use tide::http::{Method, Request};
+    /// use tide::StatusCode;
+    ///
+    /// # async_std::task::block_on(async {
+    /// let readme = std::fs::read("README.md").unwrap();
+    ///
+    /// let mut req = Request::new(Method::Get, "http://example.com/README.md");
+    /// req.insert_header("Range", "bytes=2-5");
+    /// let file = File::open("README.md").await.unwrap();
+    /// let mut resp = file.into_response_for(&req).unwrap();
+    /// assert_eq!(resp.status(), StatusCode::PartialContent);
+    /// assert_eq!(resp["Content-Range"], format!("bytes 2-5/{}", readme.len()).as_str());
+    /// assert_eq!(resp["Content-Length"], "4");
+    /// assert_eq!(resp.take_body().into_bytes().await.unwrap(), &readme[2..6]);
+    ///
+    /// req.insert_header("Range", "bytes=-3");
+    /// let file = File::open("README.md").await.unwrap();
+    /// let mut resp = file.into_response_for(&req).unwrap();
+    /// assert_eq!(resp.take_body().into_bytes().await.unwrap(), &readme[readme.len() - 3..]);
+    ///
+    /// req.insert_header("Range", "bytes=100000000-");
+    /// let file = File::open("README.md").await.unwrap();
+    /// let resp = file.into_response_for(&req).unwrap();
+    /// assert_eq!(resp.status(), StatusCode::RequestedRangeNotSatisfiable);
+    /// assert_eq!(resp["Content-Range"], format!("bytes */{}", readme.len()).as_str());
+    ///
+    /// req.insert_header("Range", "bytes=0-1, 4-5");
+    /// let file = File::open("README.md").await.unwrap();
+    /// let resp = file.into_response_for(&req).unwrap();
+    /// assert_eq!(resp.status(), StatusCode::Ok);
+    /// # });
+    /// ```
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        let range = match req.header("Range") {
+            Some(range) if req.method() == Method::Get => parse_range(range.as_str(), self.len),
+            _ => return self.response(None),
+        };
+
+        match range {
+            Range::Whole => self.response(None),
+            Range::Partial(start, end) => self.response(Some((start, end))),
+            Range::Unsatisfiable => {
+                let mut resp = tide::Response::new(StatusCode::RequestedRangeNotSatisfiable);
+                resp.insert_header(headers::CONTENT_RANGE, format!("bytes */{}", self.len));
+                Ok(resp)
+            }
+        }
+    }
+}
+
+enum Range {
+    Whole,
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+/// Parse a single byte range, multiple ranges and invalid headers are ignored so the whole file
+/// is sent
+fn parse_range(header: &str, len: u64) -> Range {
+    let spec = match header.trim().strip_prefix("bytes=") {
+        Some(spec) if !spec.contains(',') => spec.trim(),
+        _ => return Range::Whole,
+    };
+    let (start, end) = match spec.split_once('-') {
+        Some(bounds) => bounds,
+        None => return Range::Whole,
+    };
+
+    let (start, end) = if start.is_empty() {
+        match end.parse::<u64>() {
+            Ok(0) => return Range::Unsatisfiable,
+            Ok(suffix) => (len.saturating_sub(suffix), len.saturating_sub(1)),
+            Err(_) => return Range::Whole,
+        }
+    } else {
+        let start = match start.parse::<u64>() {
+            Ok(start) => start,
+            Err(_) => return Range::Whole,
+        };
+        let end = match end {
+            "" => len.saturating_sub(1),
+            end => match end.parse::<u64>() {
+                Ok(end) if end >= start => end.min(len.saturating_sub(1)),
+                _ => return Range::Whole,
+            },
+        };
+        (start, end)
+    };
+
+    if start >= len {
+        Range::Unsatisfiable
+    } else {
+        Range::Partial(start, end)
+    }
+}
+
+/// Reads `remaining` bytes from the file, after seeking to `start` on the first read
+struct RangeReader {
+    file: fs::File,
+    start: u64,
+    remaining: u64,
+    seeked: bool,
+}
+
+impl Read for RangeReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        if !this.seeked {
+            futures::ready!(Pin::new(&mut this.file).poll_seek(cx, SeekFrom::Start(this.start)))?;
+            this.seeked = true;
+        }
+
+        let max = buf.len().min(this.remaining as usize);
+        if max == 0 {
+            return Poll::Ready(Ok(0));
+        }
+        let read = futures::ready!(Pin::new(&mut this.file).poll_read(cx, &mut buf[..max]))?;
+        this.remaining -= read as u64;
+        Poll::Ready(Ok(read))
+    }
+}
+
+/// Serves the file at the path, the same as `File`
+///
+/// The file is opened when the response is built, which blocks briefly as `Responder` can't
+/// await. Use `File::open` to open it asynchronously instead.
+/// ```
+/// use hightide::Responder;
+/// use std::path::PathBuf;
+/// use tide::StatusCode;
+///
+/// let resp = PathBuf::from("README.md").into_response().unwrap();
+/// assert_eq!(resp.status(), StatusCode::Ok);
+/// assert!(resp.header("Content-Length").is_some());
+///
+/// let err = PathBuf::from("no/such/file.txt").into_response().unwrap_err();
+/// assert_eq!(err.status(), StatusCode::NotFound);
+/// ```
+impl Responder for PathBuf {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        File::open_blocking(self)?.into_response()
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        File::open_blocking(self)?.into_response_for(req)
+    }
+}
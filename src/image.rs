@@ -0,0 +1,56 @@
+use crate::stream::body_from_stream;
+use crate::Responder;
+use ::image::{DynamicImage, ImageFormat};
+use futures::stream;
+use std::io::{self, Cursor};
+use tide::http::headers;
+use tide::StatusCode;
+
+/// A responder which encodes an image (requires the `image` feature)
+///
+/// The `png`, `jpeg` and `webp` encoders are enabled. Encoding is CPU heavy so it is run with
+/// `async_std::task::spawn_blocking`, not on the async executor. It starts when the body is
+/// first read, so a response which is dropped without being sent is never encoded. The content
+/// type is set from the format. JPEG has no alpha channel so images are converted to RGB first.
+///
+/// Encoding happens while the response is being sent, so an encoding error (such as a format
+/// without an enabled encoder) can't change the status, instead the body stops with the error
+/// and the connection is closed.
+/// ```
+/// use hightide::{Image, Responder};
+/// use image::{DynamicImage, ImageFormat};
+///
+/// # async_std::task::block_on(async {
+/// let img = DynamicImage::new_rgba8(2, 2);
+/// let mut resp = Image(img, ImageFormat::Png).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "image/png");
+///
+/// let bytes = resp.take_body().into_bytes().await.unwrap();
+/// assert_eq!(&bytes[1..4], b"PNG");
+/// # });
+/// ```
+pub struct Image(pub DynamicImage, pub ImageFormat);
+
+impl Responder for Image {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let Image(img, format) = self;
+        // spawn_blocking starts the work straight away, so only call it once the body is read
+        let encoded =
+            async move { async_std::task::spawn_blocking(move || encode(img, format)).await };
+
+        let mut resp = tide::Response::new(StatusCode::Ok);
+        resp.set_body(body_from_stream(stream::once(encoded)));
+        resp.insert_header(headers::CONTENT_TYPE, format.to_mime_type());
+        Ok(resp)
+    }
+}
+
+fn encode(img: DynamicImage, format: ImageFormat) -> io::Result<Vec<u8>> {
+    let img = match format {
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(img.into_rgb8()),
+        _ => img,
+    };
+    let mut bytes = Cursor::new(Vec::new());
+    img.write_to(&mut bytes, format).map_err(io::Error::other)?;
+    Ok(bytes.into_inner())
+}
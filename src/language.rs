@@ -0,0 +1,81 @@
+use crate::{checked_header_value, Response};
+
+impl Response {
+    /// Set the `Content-Language` header to a list of language tags
+    ///
+    /// Returns an error if a tag is not a valid header value
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// let resp = Response::ok().content_language(&["en", "fr-CA"]).unwrap().into_inner();
+    /// assert_eq!(resp["Content-Language"], "en, fr-CA");
+    ///
+    /// assert!(Response::ok().content_language(&["en\r\nX-Bad: 1"]).is_err());
+    /// ```
+    pub fn content_language(mut self, langs: &[&str]) -> tide::Result<Self> {
+        let value = checked_header_value(&langs.join(", "))?;
+        self.inner.insert_header("Content-Language", value);
+        Ok(self)
+    }
+}
+
+/// Pick the best match from `available` for an `Accept-Language` header, see
+/// `RequestExt::preferred_language`
+pub(crate) fn preferred_language<'a>(
+    header: Option<&str>,
+    available: &[&'a str],
+) -> Option<&'a str> {
+    let header = match header {
+        Some(header) => header,
+        None => return available.first().copied(),
+    };
+
+    let (mut ranges, excluded): (Vec<_>, Vec<_>) = header
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim();
+            let weight = match parts.next() {
+                Some(q) => q.trim().strip_prefix("q=")?.parse().ok()?,
+                None => 1.0,
+            };
+            if tag.is_empty() {
+                None
+            } else {
+                Some((tag, weight))
+            }
+        })
+        .partition(|(_, weight)| *weight > 0.0);
+    // stable sort keeps the header order for equal weights
+    ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    ranges.into_iter().find_map(|(range, _)| {
+        if range == "*" {
+            // the wildcard matches anything not explicitly excluded with q=0
+            return available
+                .iter()
+                .find(|lang| {
+                    !excluded.iter().any(|(range, _)| {
+                        lang.eq_ignore_ascii_case(range) || is_prefix(range, lang)
+                    })
+                })
+                .copied();
+        }
+        available
+            .iter()
+            .find(|lang| lang.eq_ignore_ascii_case(range))
+            .or_else(|| {
+                available
+                    .iter()
+                    .find(|lang| is_prefix(range, lang) || is_prefix(lang, range))
+            })
+            .copied()
+    })
+}
+
+/// Check if `prefix` is a prefix of `tag` ending at a subtag, so `en` is a prefix of `en-US`
+fn is_prefix(prefix: &str, tag: &str) -> bool {
+    tag.len() > prefix.len()
+        && tag.as_bytes()[prefix.len()] == b'-'
+        && tag[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
@@ -7,15 +7,128 @@
 //! Hightide also includes a Response type that is easier to use than the one provided by
 //! tide. It has shortcut methods for setting the body to a JSON or Form payload, and for adding
 //! typed headers from the `hyperx` crate.
+//!
+//! With the `derive` feature enabled `Responder` can be derived for structs, see
+//! `hightide_derive::Responder` for the available attributes.
+//!
+//! Responders which depend on the incoming request (such as `Negotiate`, `Conditional` and
+//! `File`) implement `Responder::into_response_for`, which `wrap` (and every other `wrap_*`
+//! function) calls with a copy of the request. There is no separate trait for this, so every
+//! responder works with `wrap` and existing `Responder` implementations keep working unchanged.
+//! The copy has no body, and the app state and route parameters are not available since the
+//! endpoint consumes the request.
+//! ```
+//! use hightide::{Responder, RequestExt};
+//! use hyperx::header::UserAgent;
+//! use tide::http;
+//!
+//! struct Greeting;
+//!
+//! impl Responder for Greeting {
+//!     fn into_response(self) -> tide::Result<tide::Response> {
+//!         "Hello".into_response()
+//!     }
+//!
+//!     fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+//!         match req.typed_header::<UserAgent>()? {
+//!             Some(agent) => format!("Hello {}", agent).into_response(),
+//!             None => self.into_response(),
+//!         }
+//!     }
+//! }
+//!
+//! # async_std::task::block_on(async {
+//! let mut app = tide::new();
+//! app.at("/").get(hightide::wrap(|_| async { Greeting }));
+//!
+//! let mut req = http::Request::new(http::Method::Get, "http://localhost/");
+//! req.insert_header("User-Agent", "curl");
+//! let mut resp: http::Response = app.respond(req).await.unwrap();
+//! assert_eq!(resp.body_string().await.unwrap(), "Hello curl");
+//! # });
+//! ```
 
 use async_trait::async_trait;
-use futures::Future;
+use futures::{Future, FutureExt};
 use hyperx::header::Header;
-use std::fmt::Display;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
+use std::fmt::{self, Display};
+use std::time::Duration;
 use tide::convert::Serialize;
-use tide::http::headers::{HeaderName, HeaderValue, ToHeaderValues};
+use tide::http::cookies::Cookie;
+use tide::http::headers::{self, HeaderName, HeaderValue, HeaderValues, ToHeaderValues};
+use tide::http::trailers::{self, Trailers};
+use tide::http::{self, mime, Mime, Version};
 use tide::{Body, Request, StatusCode};
 
+mod auth;
+mod boxed;
+mod cache;
+#[cfg(feature = "compression")]
+mod compression;
+mod conditional;
+mod cors;
+#[cfg(feature = "csv")]
+mod csv;
+mod decorate;
+mod disposition;
+mod error;
+mod file;
+#[cfg(feature = "image")]
+mod image;
+mod language;
+mod multipart;
+mod negotiate;
+mod problem;
+mod proxy;
+mod request;
+mod request_id;
+mod sse;
+pub mod status;
+mod stream;
+#[cfg(any(feature = "tera", feature = "askama"))]
+mod template;
+mod vary;
+mod with_headers;
+
+#[cfg(feature = "csv")]
+pub use crate::csv::Csv;
+#[cfg(feature = "image")]
+pub use crate::image::Image;
+pub use auth::Challenge;
+pub use boxed::{BoxedResponder, ResponderObject};
+pub use cache::CacheControl;
+#[cfg(feature = "compression")]
+pub use compression::Encoding;
+pub use conditional::Conditional;
+pub use decorate::{
+    wrap_head, wrap_logged, wrap_timed, wrap_with_errors, EndpointExt, HighErrors, HighHead,
+    HighLogged, HighTimed,
+};
+pub use error::{ErrorFormatter, JsonErrors, ResponderError};
+pub use file::File;
+pub use multipart::{Multipart, MultipartPart};
+pub use negotiate::Negotiate;
+pub use problem::Problem;
+pub use request::{body_form, body_json, query, RequestExt};
+pub use request_id::{wrap_request_id, HighRequestId, RequestId};
+pub use sse::{Sse, SseEvent};
+pub use stream::{JsonLines, JsonStream, ReaderBody, StreamBody};
+#[cfg(feature = "askama")]
+pub use template::Askama;
+pub use with_headers::WithHeaders;
+
+#[cfg(feature = "derive")]
+pub use hightide_derive::Responder;
+
+// Not public API. Referenced by macro-generated code.
+#[doc(hidden)]
+pub mod __private {
+    pub use tide;
+}
+
 /// This trait is implemented for all the common types you can return from an endpoint
 ///
 /// It's also implemented for `tide::Response` and `hightide::Response` for compatibility.
@@ -25,6 +138,8 @@ use tide::{Body, Request, StatusCode};
 /// ```
 /// use hightide::{Responder, Json};
 /// use tide::{StatusCode, Request};
+/// # #[derive(serde::Serialize)]
+/// # struct MyData { id: u32, key: &'static str }
 ///
 /// fn example_1(_: Request<()>) -> impl Responder {
 ///     // return status code
@@ -51,15 +166,59 @@ use tide::{Body, Request, StatusCode};
 ///     // (also works the return type as `impl Responder` as long as Rust can infer the function returns `tide::Result`)
 ///     Ok((StatusCode::Conflict, "Already Exists"))
 /// }
+///
+/// fn example_6(_: Request<()>) -> impl Responder {
+///     // optional values - `None` becomes a 404 Not Found with an empty body
+///     Some("Found it")
+/// }
+///
+/// fn example_7(_: Request<()>) -> impl Responder {
+///     // numbers and bools are returned as plain text
+///     42
+/// }
 /// ```
 pub trait Responder {
     fn into_response(self) -> tide::Result<tide::Response>;
+
+    /// Convert into a response with access to the request that is being responded to
+    ///
+    /// The request is a copy of the original with an empty body, so only the method, url and
+    /// headers are available. The default implementation ignores the request and calls
+    /// `into_response`, override it for responders that depend on the request such as `Negotiate`.
+    /// Wrapper types should forward this to their inner responder.
+    fn into_response_for(self, _req: &http::Request) -> tide::Result<tide::Response>
+    where
+        Self: Sized,
+    {
+        self.into_response()
+    }
+
+    /// Add a header to the response, see `WithHeaders`
+    fn with_header(self, name: impl Into<HeaderName>, value: impl Into<String>) -> WithHeaders<Self>
+    where
+        Self: Sized,
+    {
+        WithHeaders::new(self).with_header(name, value)
+    }
+
+    /// Erase the type of the responder, see `BoxedResponder`
+    fn boxed(self) -> BoxedResponder
+    where
+        Self: Sized + Send + 'static,
+    {
+        Box::new(self)
+    }
 }
 
 /// Wraps the endpoint to bypass the orphan rules - pretty much ignore this one
 pub struct High<F>(F);
 
 /// Wrap an endpoint to allow it to return the Responder types
+///
+/// To support `Responder::into_response_for` the method, url and headers of every request are
+/// cloned before the endpoint runs, even if the responder doesn't use them. This is a small
+/// cost per request (one allocation for the url and one per header), which all the `wrap_*`
+/// functions share.
 pub fn wrap<F>(f: F) -> High<F> {
     High(f)
 }
@@ -74,9 +233,75 @@ where
     Res: Responder + 'static,
 {
     async fn call(&self, req: Request<State>) -> tide::Result<tide::Response> {
-        let fut = (self.0)(req);
-        let res = fut.await;
-        res.into_response()
+        let ctx = request_context(&req);
+        respond(ctx, (self.0)(req)).await
+    }
+}
+
+/// Copy the parts of the request that `Responder::into_response_for` can see
+fn request_context<State>(req: &Request<State>) -> http::Request {
+    AsRef::<http::Request>::as_ref(req).clone()
+}
+
+/// Run the endpoint future and convert its result into a response for the request
+async fn respond<Fut, Res>(ctx: http::Request, fut: Fut) -> tide::Result<tide::Response>
+where
+    Fut: Future<Output = Res>,
+    Res: Responder,
+{
+    fut.await.into_response_for(&ctx)
+}
+
+/// Wraps an endpoint which takes extra data, see `wrap_with`
+pub struct HighWith<D, F> {
+    data: D,
+    f: F,
+}
+
+/// Wrap an endpoint to allow it to return the Responder types, passing a clone of `data` to
+/// each call along with the request
+///
+/// This is handy for sharing things like a database pool (usually in an `Arc`) with a few
+/// endpoints, without adding them to the tide `State`.
+/// ```
+/// use hightide::{wrap_with, Responder};
+/// use std::sync::Arc;
+/// use tide::Request;
+///
+/// struct Config {
+///     greeting: String,
+/// }
+///
+/// async fn hello(config: Arc<Config>, _: Request<()>) -> impl Responder {
+///     config.greeting.clone()
+/// }
+///
+/// # async_std::task::block_on(async {
+/// let config = Arc::new(Config { greeting: "Hello".to_owned() });
+/// let mut app = tide::new();
+/// app.at("/").get(wrap_with(config, hello));
+///
+/// let req = tide::http::Request::new(tide::http::Method::Get, "http://localhost/");
+/// let mut resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp.body_string().await.unwrap(), "Hello");
+/// # });
+/// ```
+pub fn wrap_with<D, F>(data: D, f: F) -> HighWith<D, F> {
+    HighWith { data, f }
+}
+
+#[async_trait]
+impl<State, D, F, Fut, Res> tide::Endpoint<State> for HighWith<D, F>
+where
+    State: Clone + Send + Sync + 'static,
+    D: Clone + Send + Sync + 'static,
+    F: Send + Sync + 'static + Fn(D, Request<State>) -> Fut,
+    Fut: Future<Output = Res> + Send + 'static,
+    Res: Responder + 'static,
+{
+    async fn call(&self, req: Request<State>) -> tide::Result<tide::Response> {
+        let ctx = request_context(&req);
+        respond(ctx, (self.f)(self.data.clone(), req)).await
     }
 }
 
@@ -85,27 +310,169 @@ where
 /// ```
 /// use hightide::{Responder, Response};
 /// use tide::Request;
+/// # #[derive(serde::Serialize)]
+/// # struct MyData { id: u32 }
 /// fn example(_: Request<()>) -> impl Responder {
-///     Response::ok().json(MyData{...})
+///     Response::ok().json(MyData{ id: 0 })
 /// }
 /// ```
 pub struct Response {
     inner: tide::Response,
+    trailers: Option<Trailers>,
+}
+
+/// Parse a header value, rejecting control characters
+///
+/// `HeaderValue` only checks the value is ASCII, and the server writes header values out
+/// unchanged, so a CR or LF in the value would end the header early and let the rest of the
+/// value inject headers into the response.
+pub(crate) fn checked_header_value(value: &str) -> tide::Result<HeaderValue> {
+    if value.bytes().any(|b| b.is_ascii_control()) {
+        return Err(tide::Error::from_str(
+            StatusCode::InternalServerError,
+            "invalid header value",
+        ));
+    }
+    value.parse()
+}
+
+fn location_value(uri: &str) -> tide::Result<HeaderValue> {
+    checked_header_value(uri).map_err(|_| {
+        tide::Error::from_str(StatusCode::InternalServerError, "invalid Location header")
+    })
+}
+
+/// Shows the status, headers and body length, the body itself is not shown
+/// ```
+/// use hightide::Response;
+///
+/// let resp = Response::ok().text("hello");
+/// assert_eq!(
+///     format!("{:?}", resp),
+///     r#"Response { status: Ok, headers: {"content-type": ["text/plain;charset=utf-8"]}, body_len: Some(5) }"#
+/// );
+/// ```
+impl fmt::Debug for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response")
+            .field("status", &self.inner.status())
+            .field("headers", &DebugHeaders(&self.inner))
+            .field("body_len", &self.inner.len())
+            .finish()
+    }
+}
+
+struct DebugHeaders<'a>(&'a tide::Response);
+
+impl fmt::Debug for DebugHeaders<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.0.iter().map(|(name, values)| {
+                let values = values.iter().map(|v| v.as_str()).collect::<Vec<_>>();
+                (name.as_str(), values)
+            }))
+            .finish()
+    }
 }
 
 impl Response {
     /// Create an empty response with status code OK (200)
     pub fn ok() -> Self {
-        Self {
-            inner: tide::Response::from(StatusCode::Ok),
-        }
+        Self::from(tide::Response::from(StatusCode::Ok))
     }
 
     /// Create an empty response with the given status code
+    ///
+    /// The reason phrase sent is always the standard one for the status code (for example
+    /// `404 Not Found`), `http_types` has no way to set a custom phrase. HTTP/2 doesn't send
+    /// reason phrases at all, so clients shouldn't rely on them.
     pub fn status(s: StatusCode) -> Self {
-        Self {
-            inner: tide::Response::from(s),
-        }
+        Self::from(tide::Response::from(s))
+    }
+
+    /// Create a response with the given status code and a JSON payload
+    /// ```
+    /// use hightide::{Responder, Response};
+    /// use tide::{Request, StatusCode};
+    /// # #[derive(serde::Serialize)]
+    /// # struct MyData { id: u32 }
+    /// fn create(_: Request<()>) -> tide::Result<impl Responder> {
+    ///     Response::json_with_status(StatusCode::Created, MyData { id: 42 })
+    /// }
+    ///
+    /// let resp = Response::json_with_status(StatusCode::Created, vec![1, 2]).unwrap().into_inner();
+    /// assert_eq!(resp.status(), StatusCode::Created);
+    /// assert_eq!(resp["Content-Type"], "application/json");
+    /// ```
+    pub fn json_with_status(status: StatusCode, body: impl Serialize) -> tide::Result<Self> {
+        Self::status(status).json(body)
+    }
+
+    /// Create an Ok (200) response with a JSON payload and some headers
+    ///
+    /// The headers are any collection of name and value pairs, the same as `headers`: the names
+    /// convert into `HeaderName` (such as `&str`) and the values are anything convertible to
+    /// header values (such as `&str`, `String` or `HeaderValue`). Each header replaces any value
+    /// already set. Returns an error if the body fails to serialize or a value is not a valid
+    /// header value (including one containing CR or LF).
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// let users = vec!["steve", "sue"];
+    /// let resp = Response::json_with_headers(
+    ///     &users,
+    ///     vec![
+    ///         ("X-Total-Count", "2".to_owned()),
+    ///         ("Link", "</users?page=2>; rel=\"next\"".to_owned()),
+    ///     ],
+    /// )
+    /// .unwrap()
+    /// .into_inner();
+    /// assert_eq!(resp["Content-Type"], "application/json");
+    /// assert_eq!(resp["X-Total-Count"], "2");
+    /// assert_eq!(resp["Link"], "</users?page=2>; rel=\"next\"");
+    ///
+    /// let bad = vec![("X-Bad", "a\r\nSet-Cookie: evil=1")];
+    /// assert!(Response::json_with_headers(&users, bad).is_err());
+    /// ```
+    pub fn json_with_headers<I, N, V>(body: impl Serialize, headers: I) -> tide::Result<Self>
+    where
+        I: IntoIterator<Item = (N, V)>,
+        N: Into<HeaderName>,
+        V: ToHeaderValues,
+    {
+        Self::ok().json(body)?.headers(headers)
+    }
+
+    /// Change the status code of the response
+    /// ```
+    /// use hightide::Response;
+    /// use tide::StatusCode;
+    ///
+    /// # fn main() -> tide::Result<()> {
+    /// let resp = Response::ok().json(vec![1])?.with_status(StatusCode::Created).into_inner();
+    /// assert_eq!(resp.status(), StatusCode::Created);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_status(mut self, s: StatusCode) -> Self {
+        self.inner.set_status(s);
+        self
+    }
+
+    /// Set the HTTP version of the response
+    ///
+    /// This is only a hint, the version actually sent is decided by the server and transport.
+    /// ```
+    /// use hightide::Response;
+    /// use tide::http::Version;
+    ///
+    /// let resp: tide::http::Response = Response::ok().version(Version::Http1_0).into_inner().into();
+    /// assert_eq!(resp.version(), Some(Version::Http1_0));
+    /// ```
+    pub fn version(mut self, v: Version) -> Self {
+        AsMut::<http::Response>::as_mut(&mut self.inner).set_version(Some(v));
+        self
     }
 
     /// Set the body of the response
@@ -120,31 +487,529 @@ impl Response {
         Ok(self)
     }
 
+    /// Set the body of the response to a pretty-printed JSON payload, for responses meant to be
+    /// read by people
+    pub fn json_pretty(mut self, body: impl Serialize) -> tide::Result<Self> {
+        self.inner.set_body(serde_json::to_vec_pretty(&body)?);
+        self.inner.set_content_type(mime::JSON);
+        Ok(self)
+    }
+
     /// Set the body of the response to form data
     pub fn form(mut self, body: impl Serialize) -> tide::Result<Self> {
         self.inner.set_body(Body::from_form(&body)?);
         Ok(self)
     }
 
-    /// Set a header (from the `hyperx` typed headers)
-    pub fn header<H: Header + Display>(mut self, h: H) -> Self {
-        self.inner.insert_header(
-            H::header_name(),
-            h.to_string()
-                .parse::<HeaderValue>()
-                .expect("invalid header"),
-        );
+    /// Set the body of the response to a YAML payload (requires the `yaml` feature)
+    #[cfg(feature = "yaml")]
+    pub fn yaml(mut self, body: impl Serialize) -> tide::Result<Self> {
+        self.inner.set_body(serde_yaml::to_string(&body)?);
+        self.inner.set_content_type("application/x-yaml");
+        Ok(self)
+    }
+
+    /// Set the body of the response to a MessagePack payload (requires the `msgpack` feature)
+    ///
+    /// Structs are serialized as maps with their field names, like the JSON encoding
+    #[cfg(feature = "msgpack")]
+    pub fn msgpack(mut self, body: impl Serialize) -> tide::Result<Self> {
+        self.inner.set_body(rmp_serde::to_vec_named(&body)?);
+        self.inner.set_content_type("application/msgpack");
+        Ok(self)
+    }
+
+    /// Set the body of the response to a CBOR payload (requires the `cbor` feature)
+    #[cfg(feature = "cbor")]
+    pub fn cbor(mut self, body: impl Serialize) -> tide::Result<Self> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&body, &mut bytes)?;
+        self.inner.set_body(bytes);
+        self.inner.set_content_type("application/cbor");
+        Ok(self)
+    }
+
+    /// Set the body of the response to an XML payload (requires the `xml` feature)
+    ///
+    /// Serialization uses `quick-xml`, the root element is named after the type being
+    /// serialized. Values without a name, such as sequences, maps and primitives, can't be
+    /// serialized at the top level and return an error - wrap them in a struct instead.
+    #[cfg(feature = "xml")]
+    pub fn xml(mut self, body: impl Serialize) -> tide::Result<Self> {
+        self.inner.set_body(quick_xml::se::to_string(&body)?);
+        self.inner.set_content_type(mime::XML);
+        Ok(self)
+    }
+
+    /// Set the body of the response to an HTML string, with content type `text/html`
+    pub fn html(mut self, body: impl Into<String>) -> Self {
+        self.inner.set_body(body.into());
+        self.inner.set_content_type(mime::HTML);
+        self
+    }
+
+    /// Set the body of the response to a plain text string, with content type `text/plain`
+    pub fn text(mut self, body: impl Into<String>) -> Self {
+        self.inner.set_body(body.into());
+        self.inner.set_content_type(mime::PLAIN);
+        self
+    }
+
+    /// Set the `Content-Type` header
+    ///
+    /// String literals are parsed into a `Mime`, which panics if they are not a valid media type
+    /// ```
+    /// use hightide::Response;
+    /// use tide::http::mime;
+    ///
+    /// let resp = Response::ok().body("a,b").content_type("text/csv").into_inner();
+    /// assert_eq!(resp["Content-Type"], "text/csv");
+    ///
+    /// let resp = Response::ok().content_type(mime::SVG).into_inner();
+    /// assert_eq!(resp["Content-Type"], "image/svg+xml");
+    /// ```
+    pub fn content_type(mut self, mime: impl Into<Mime>) -> Self {
+        self.inner.set_content_type(mime);
         self
     }
 
+    /// Set a header (from the `hyperx` typed headers)
+    ///
+    /// Panics if the header doesn't produce a valid header value, see `try_header` for a
+    /// non-panicking version
+    pub fn header<H: Header + Display>(self, h: H) -> Self {
+        self.try_header(h).expect("invalid header")
+    }
+
+    /// Set a header (from the `hyperx` typed headers), returning an error if the header doesn't
+    /// produce a valid header value
+    pub fn try_header<H: Header + Display>(mut self, h: H) -> tide::Result<Self> {
+        let value = h.to_string().parse::<HeaderValue>()?;
+        self.inner.insert_header(H::header_name(), value);
+        Ok(self)
+    }
+
+    /// Set the `Location` header
+    ///
+    /// Panics if the location is not a valid header value, use `try_location` for locations which
+    /// aren't known to be valid
+    pub fn location(self, uri: impl AsRef<str>) -> Self {
+        self.try_location(uri).expect("invalid header")
+    }
+
+    /// Set the `Location` header, returning an error if the location is not a valid header value
+    /// (it has non-ASCII or control characters)
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// let resp = Response::ok().try_location("/users/42").unwrap().into_inner();
+    /// assert_eq!(resp["Location"], "/users/42");
+    ///
+    /// assert!(Response::ok().try_location("/users/\r\nSet-Cookie: a=b").is_err());
+    /// assert!(Response::ok().try_location("/caf\u{e9}").is_err());
+    /// ```
+    pub fn try_location(mut self, uri: impl AsRef<str>) -> tide::Result<Self> {
+        self.inner
+            .insert_header(headers::LOCATION, location_value(uri.as_ref())?);
+        Ok(self)
+    }
+
     /// Set a raw header (from the `http_types` crate)
+    ///
+    /// This replaces any values already set for the header, use `append_header` to add another
+    /// value instead.
     pub fn raw_header(mut self, name: impl Into<HeaderName>, key: impl ToHeaderValues) -> Self {
         self.inner.insert_header(name, key);
         self
     }
 
+    /// Add a value to a raw header (from the `http_types` crate)
+    ///
+    /// Unlike `raw_header` this keeps any values already set, for headers which can appear more
+    /// than once such as `Vary` or `Link`.
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// let resp = Response::ok()
+    ///     .raw_header("Link", "</a.css>; rel=preload")
+    ///     .append_header("Link", "</b.js>; rel=preload")
+    ///     .into_inner();
+    /// let links: Vec<_> = resp["Link"].iter().map(|v| v.as_str()).collect();
+    /// assert_eq!(links, ["</a.css>; rel=preload", "</b.js>; rel=preload"]);
+    /// ```
+    pub fn append_header(mut self, name: impl Into<HeaderName>, key: impl ToHeaderValues) -> Self {
+        self.inner.append_header(name, key);
+        self
+    }
+
+    /// Remove a header and all its values, this does nothing if the header isn't set
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// let resp = Response::ok()
+    ///     .raw_header("Server", "upstream/1.0")
+    ///     .remove_header("Server")
+    ///     .remove_header("X-Not-Set")
+    ///     .into_inner();
+    /// assert!(resp.header("Server").is_none());
+    /// ```
+    pub fn remove_header(mut self, name: impl Into<HeaderName>) -> Self {
+        self.inner.remove_header(name);
+        self
+    }
+
+    /// Set several raw headers, replacing any values already set for each header
+    ///
+    /// The values can be anything convertible to header values, such as `HeaderValue` or strings.
+    /// Returns an error if a value is not a valid header value or contains control characters
+    /// such as CR or LF.
+    /// ```
+    /// use hightide::Response;
+    /// use tide::http::headers::{HeaderName, HeaderValue};
+    ///
+    /// let headers = vec![
+    ///     (HeaderName::from("X-Shard"), HeaderValue::from_bytes(b"7".to_vec()).unwrap()),
+    ///     (HeaderName::from("X-Region"), "eu-west".parse().unwrap()),
+    /// ];
+    /// let resp = Response::ok().headers(headers).unwrap().into_inner();
+    /// assert_eq!(resp["X-Shard"], "7");
+    /// assert_eq!(resp["X-Region"], "eu-west");
+    ///
+    /// assert!(Response::ok().headers(vec![("X-Bad", "caf\u{e9}")]).is_err());
+    /// assert!(Response::ok().headers(vec![("X-Bad", "a\r\nSet-Cookie: evil=1")]).is_err());
+    /// ```
+    pub fn headers<I, N, V>(mut self, headers: I) -> tide::Result<Self>
+    where
+        I: IntoIterator<Item = (N, V)>,
+        N: Into<HeaderName>,
+        V: ToHeaderValues,
+    {
+        for (name, values) in headers {
+            let values = values
+                .to_header_values()?
+                .map(|v| checked_header_value(v.as_str()))
+                .collect::<tide::Result<Vec<_>>>()?;
+            self.inner.insert_header(name, &values[..]);
+        }
+        Ok(self)
+    }
+
+    /// Copy all the headers from another response
+    ///
+    /// Values are appended, so a header set on both responses ends up with the values from both.
+    /// Use `raw_header` afterwards if one value should win.
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// let template = Response::ok()
+    ///     .raw_header("Access-Control-Allow-Origin", "*")
+    ///     .raw_header("Vary", "Origin")
+    ///     .into_inner();
+    ///
+    /// let resp = Response::ok()
+    ///     .raw_header("Vary", "Accept")
+    ///     .merge_headers_from(&template)
+    ///     .into_inner();
+    /// assert_eq!(resp["Access-Control-Allow-Origin"], "*");
+    /// let vary: Vec<_> = resp["Vary"].iter().map(|v| v.as_str()).collect();
+    /// assert_eq!(vary, ["Accept", "Origin"]);
+    /// ```
+    pub fn merge_headers_from(mut self, other: &tide::Response) -> Self {
+        for (name, values) in other.iter() {
+            self.inner.append_header(name.clone(), values);
+        }
+        self
+    }
+
+    /// Add a trailer, a header which is sent after the body
+    ///
+    /// The trailer must be known when the response is built, use `trailer_sender` for trailers
+    /// which are computed while the body is sent, such as a checksum.
+    ///
+    /// Trailers are only sent by transports which support them, tide's HTTP/1.1 server
+    /// (`async-h1`) doesn't write response trailers so they are dropped. They can still be read
+    /// in process with `tide::http::Response::recv_trailers`, for example in tests or a custom
+    /// transport.
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// # async_std::task::block_on(async {
+    /// let mut resp: tide::http::Response = Response::ok()
+    ///     .body("payload")
+    ///     .trailer("Content-MD5", "a1b2c3")
+    ///     .into_inner()
+    ///     .into();
+    /// let trailers = resp.recv_trailers().await.unwrap();
+    /// assert_eq!(trailers["Content-MD5"], "a1b2c3");
+    /// # });
+    /// ```
+    pub fn trailer(mut self, name: impl Into<HeaderName>, value: impl ToHeaderValues) -> Self {
+        self.trailers
+            .get_or_insert_with(Trailers::new)
+            .insert(name, value);
+        self
+    }
+
+    /// Take a sender for trailers which are only known later, for example after a streaming
+    /// body has finished
+    ///
+    /// Trailers already added with `trailer` are sent along with the ones given to the sender,
+    /// trailers added with `trailer` after this are not sent. The same transport caveats as
+    /// `trailer` apply. Panics if called twice.
+    /// ```
+    /// use hightide::Response;
+    /// use tide::http::trailers::{self, Trailers};
+    ///
+    /// # async_std::task::block_on(async {
+    /// let mut resp = Response::ok().body("payload").trailer("X-Static", "1");
+    /// let sender = resp.trailer_sender();
+    /// let mut resp: tide::http::Response = resp.into_inner().into();
+    ///
+    /// async_std::task::spawn(async move {
+    ///     // ... once the body has been produced
+    ///     let mut trailers = Trailers::new();
+    ///     trailers.insert("Content-MD5", "a1b2c3");
+    ///     sender.send(trailers).await;
+    /// });
+    ///
+    /// let trailers = resp.recv_trailers().await.unwrap();
+    /// assert_eq!(trailers["Content-MD5"], "a1b2c3");
+    /// assert_eq!(trailers["X-Static"], "1");
+    /// # });
+    /// ```
+    pub fn trailer_sender(&mut self) -> TrailerSender {
+        let sender = AsMut::<http::Response>::as_mut(&mut self.inner).send_trailers();
+        TrailerSender {
+            sender,
+            trailers: self.trailers.take().unwrap_or_else(Trailers::new),
+        }
+    }
+
+    /// Set a cookie on the response
+    ///
+    /// Each cookie with a distinct name is sent in its own `Set-Cookie` header. Setting a cookie
+    /// with the same name as one already set replaces it.
+    /// ```
+    /// use hightide::{Responder, Response};
+    /// use tide::http::cookies::Cookie;
+    /// use tide::Request;
+    /// fn login(_: Request<()>) -> impl Responder {
+    ///     Response::ok()
+    ///         .cookie(Cookie::new("session", "abc123"))
+    ///         .cookie(Cookie::new("theme", "dark"))
+    /// }
+    /// ```
+    pub fn cookie(mut self, cookie: Cookie<'static>) -> Self {
+        self.inner.insert_cookie(cookie);
+        self
+    }
+
+    /// Add a cookie in its own `Set-Cookie` header, without replacing any cookie already set
+    ///
+    /// Unlike `cookie` this never replaces a cookie with the same name, which is needed to set
+    /// cookies with the same name for different paths or domains.
+    /// ```
+    /// use hightide::Response;
+    /// use tide::http::cookies::Cookie;
+    /// use tide::http::{Method, Request};
+    ///
+    /// # async_std::task::block_on(async {
+    /// let mut app = tide::new();
+    /// app.at("/").get(hightide::wrap(|_| async {
+    ///     let mut admin = Cookie::new("pref", "compact");
+    ///     admin.set_path("/admin");
+    ///     Response::ok()
+    ///         .append_cookie(Cookie::new("session", "abc123"))
+    ///         .append_cookie(Cookie::new("pref", "dark"))
+    ///         .append_cookie(admin)
+    /// }));
+    ///
+    /// let req = Request::new(Method::Get, "http://localhost/");
+    /// let resp: tide::http::Response = app.respond(req).await.unwrap();
+    /// let cookies: Vec<_> = resp["Set-Cookie"].iter().map(|v| v.as_str()).collect();
+    /// assert_eq!(cookies, ["session=abc123", "pref=dark", "pref=compact; Path=/admin"]);
+    /// # });
+    /// ```
+    pub fn append_cookie(mut self, cookie: Cookie<'static>) -> Self {
+        self.inner
+            .append_header(headers::SET_COOKIE, cookie.encoded().to_string());
+        self
+    }
+
+    /// Remove a cookie, this sends a `Set-Cookie` header which expires the cookie on the client
+    pub fn remove_cookie(mut self, cookie: Cookie<'static>) -> Self {
+        self.inner.remove_cookie(cookie);
+        self
+    }
+
+    /// Attach a value to the response's extensions, replacing any value of the same type
+    ///
+    /// Extensions are not sent to the client, they carry data from the endpoint to middleware
+    /// which processes the response (read with `tide::Response::ext`, or `get_ext` here).
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// struct CacheKey(String);
+    ///
+    /// let resp = Response::ok().ext(CacheKey("users/42".to_owned()));
+    /// assert_eq!(resp.get_ext::<CacheKey>().unwrap().0, "users/42");
+    ///
+    /// let resp = resp.into_inner();
+    /// assert_eq!(resp.ext::<CacheKey>().unwrap().0, "users/42");
+    /// assert_eq!(resp.header_names().count(), 0);
+    /// ```
+    pub fn ext<T: Send + Sync + 'static>(mut self, val: T) -> Self {
+        self.inner.insert_ext(val);
+        self
+    }
+
+    /// Get a value from the response's extensions
+    pub fn get_ext<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.inner.ext()
+    }
+
+    /// Apply any transformation to the inner `tide::Response` and keep building
+    ///
+    /// This is the general escape hatch for anything the builder methods don't cover, without
+    /// going through `into_inner` and converting back.
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// let resp = Response::ok()
+    ///     .map_inner(|mut r| {
+    ///         r.insert_ext(42u32);
+    ///         r
+    ///     })
+    ///     .raw_header("X-Count", "1");
+    /// assert_eq!(resp.get_ext::<u32>(), Some(&42));
+    /// ```
+    pub fn map_inner<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(tide::Response) -> tide::Response,
+    {
+        self.inner = f(self.inner);
+        self
+    }
+
+    /// Apply a builder step only if `opt` is `Some`, passing it the value
+    ///
+    /// Returns the response unchanged for `None`, so optional headers don't break the chain.
+    /// ```
+    /// use hightide::{CacheControl, Response};
+    /// use std::time::Duration;
+    ///
+    /// let max_age: Option<Duration> = Some(Duration::from_secs(60));
+    /// let resp = Response::ok()
+    ///     .if_some(max_age, |r, age| r.cache_control(CacheControl::new().max_age(age)))
+    ///     .into_inner();
+    /// assert_eq!(resp["Cache-Control"], "max-age=60");
+    ///
+    /// let resp = Response::ok()
+    ///     .if_some(None, |r, age| r.cache_control(CacheControl::new().max_age(age)))
+    ///     .into_inner();
+    /// assert!(resp.header("Cache-Control").is_none());
+    /// ```
+    pub fn if_some<T, F>(self, opt: Option<T>, f: F) -> Self
+    where
+        F: FnOnce(Self, T) -> Self,
+    {
+        match opt {
+            Some(val) => f(self, val),
+            None => self,
+        }
+    }
+
+    /// Apply a fallible builder step only if `opt` is `Some`, see `if_some`
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// let location: Option<&str> = Some("/users/42");
+    /// let resp = Response::ok()
+    ///     .try_if_some(location, |r, uri| r.try_location(uri))
+    ///     .unwrap()
+    ///     .into_inner();
+    /// assert_eq!(resp["Location"], "/users/42");
+    /// ```
+    pub fn try_if_some<T, F>(self, opt: Option<T>, f: F) -> tide::Result<Self>
+    where
+        F: FnOnce(Self, T) -> tide::Result<Self>,
+    {
+        match opt {
+            Some(val) => f(self, val),
+            None => Ok(self),
+        }
+    }
+
+    /// Apply a fallible builder step, for chaining steps like `json` in the middle of a chain
+    ///
+    /// The infallible builder methods return `Self` and the fallible ones return
+    /// `tide::Result<Self>`. Use `and_then` to continue a chain with a fallible step, and
+    /// `Result::map` to continue with infallible steps after one.
+    /// ```
+    /// use hightide::Response;
+    /// use tide::StatusCode;
+    ///
+    /// let resp = Response::status(StatusCode::Created)
+    ///     .raw_header("X-Request-Id", "42")
+    ///     .and_then(|r| r.json(vec![1, 2, 3]))
+    ///     .map(|r| r.raw_header("X-Count", "3"))
+    ///     .unwrap()
+    ///     .into_inner();
+    /// assert_eq!(resp["Content-Type"], "application/json");
+    /// assert_eq!(resp["X-Count"], "3");
+    /// ```
+    pub fn and_then<F>(self, f: F) -> tide::Result<Self>
+    where
+        F: FnOnce(Self) -> tide::Result<Self>,
+    {
+        f(self)
+    }
+
+    /// Get the status code of the response
+    ///
+    /// This is named `status_code` because `Response::status` is the constructor.
+    /// ```
+    /// use hightide::Response;
+    /// use tide::StatusCode;
+    ///
+    /// let resp = Response::status(StatusCode::Created).raw_header("X-Id", "42");
+    /// assert_eq!(resp.status_code(), StatusCode::Created);
+    /// assert_eq!(resp.header_value("X-Id").unwrap(), "42");
+    /// assert!(resp.header_value("X-Missing").is_none());
+    /// ```
+    pub fn status_code(&self) -> StatusCode {
+        self.inner.status()
+    }
+
+    /// Get the values of a header of the response, if it is set
+    pub fn header_value(&self, name: impl Into<HeaderName>) -> Option<&HeaderValues> {
+        self.inner.header(name)
+    }
+
+    /// Read the whole body, leaving the response with an empty body
+    ///
+    /// This is mostly useful for inspecting responses in tests.
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// # async_std::task::block_on(async {
+    /// let mut resp = Response::ok().body("hi");
+    /// assert_eq!(resp.take_body_bytes().await.unwrap(), b"hi");
+    /// assert_eq!(resp.take_body_bytes().await.unwrap(), b"");
+    /// # });
+    /// ```
+    pub async fn take_body_bytes(&mut self) -> tide::Result<Vec<u8>> {
+        self.inner.take_body().into_bytes().await
+    }
+
     /// Consume this response and return the inner `tide::Response`
-    pub fn into_inner(self) -> tide::Response {
+    pub fn into_inner(mut self) -> tide::Response {
+        let has_sender = AsRef::<http::Response>::as_ref(&self.inner).has_trailers();
+        if let (Some(trailers), false) = (self.trailers, has_sender) {
+            let sender = AsMut::<http::Response>::as_mut(&mut self.inner).send_trailers();
+            // the channel is new and has room for the trailers, so this completes immediately
+            let _ = sender.send(trailers).now_or_never();
+        }
         self.inner
     }
 }
@@ -167,69 +1032,929 @@ impl Responder for &str {
     }
 }
 
-impl Responder for &[u8] {
+/// Returns the string the same as `&str` or `String`, depending on the variant
+/// ```
+/// use hightide::Responder;
+/// use std::borrow::Cow;
+/// use tide::Request;
+/// fn greeting(req: Request<()>) -> Cow<'static, str> {
+///     match req.param::<String>("name") {
+///         Ok(name) => Cow::Owned(format!("Hello {}", name)),
+///         Err(_) => Cow::Borrowed("Hello stranger"),
+///     }
+/// }
+/// ```
+impl Responder for Cow<'static, str> {
     fn into_response(self) -> tide::Result<tide::Response> {
-        Ok(tide::Response::from(Body::from(self)))
+        match self {
+            Cow::Borrowed(s) => s.into_response(),
+            Cow::Owned(s) => s.into_response(),
+        }
     }
 }
 
-impl<R: Responder> Responder for (StatusCode, R) {
+impl Responder for &[u8] {
     fn into_response(self) -> tide::Result<tide::Response> {
-        let mut resp = self.1.into_response()?;
-        resp.set_status(self.0);
-        Ok(resp)
+        Ok(tide::Response::from(Body::from(self)))
     }
 }
 
-/// Returns `StatusCode::NotFound` for `None`, and the inner value for `Some`
-impl<R: Responder> Responder for Option<R> {
+/// Returns the bytes with content type `application/octet-stream` the same as `&[u8]` or
+/// `Vec<u8>`, depending on the variant
+///
+/// For a different content type wrap the bytes, for example in `Bytes`.
+/// ```
+/// use hightide::{Bytes, Responder};
+/// use std::borrow::Cow;
+///
+/// static PLACEHOLDER: &[u8] = &[0x89, b'P', b'N', b'G'];
+///
+/// let data: Cow<'static, [u8]> = Cow::Borrowed(PLACEHOLDER);
+/// let resp = data.clone().into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "application/octet-stream");
+///
+/// let resp = Bytes("image/png", data).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "image/png");
+/// ```
+impl Responder for Cow<'static, [u8]> {
     fn into_response(self) -> tide::Result<tide::Response> {
         match self {
-            None => StatusCode::NotFound.into_response(),
-            Some(r) => r.into_response(),
+            Cow::Borrowed(b) => b.into_response(),
+            Cow::Owned(b) => b.into_response(),
         }
     }
 }
 
-/// A Wrapper to return a JSON payload. This can be wrapped over any `serde::Serialize` type.
+/// Returns the bytes with content type `application/octet-stream`, the same as `&[u8]`
+/// ```
+/// use hightide::Responder;
+///
+/// let resp = [0xde, 0xad, 0xbe, 0xef].into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "application/octet-stream");
+/// assert_eq!(resp.len(), Some(4));
+/// ```
+impl<const N: usize> Responder for [u8; N] {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Ok(tide::Response::from(Body::from_bytes(self.to_vec())))
+    }
+}
+
+macro_rules! display_responder {
+    ($($ty:ty),*) => {
+        $(
+            /// Returns the `Display` output as a plain text body
+            impl Responder for $ty {
+                fn into_response(self) -> tide::Result<tide::Response> {
+                    Ok(Response::ok().text(self.to_string()).into_inner())
+                }
+            }
+        )*
+    };
+}
+
+display_responder!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64, bool);
+
+/// Returns the bytes with content type `application/octet-stream`
+///
+/// To use a different content type wrap the bytes in a `Response` and set the header
 /// ```
+/// use hightide::{Responder, Response};
 /// use tide::Request;
-/// use hightide::{Responder, Json};
-/// fn returns_json(_: Request<()>) -> impl Responder {
-///     Json(vec!["an", "array"])
+/// fn image(_: Request<()>) -> impl Responder {
+///     let png: Vec<u8> = vec![0x89, b'P', b'N', b'G'];
+///     Response::ok().body(png).raw_header("Content-Type", "image/png")
 /// }
 /// ```
-pub struct Json<T: Serialize>(pub T);
+impl Responder for Vec<u8> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Ok(tide::Response::from(Body::from_bytes(self)))
+    }
+}
 
-impl<T: Serialize> Responder for Json<T> {
+/// Returns the bytes with content type `application/octet-stream` (requires the `bytes` feature)
+#[cfg(feature = "bytes")]
+impl Responder for bytes::Bytes {
     fn into_response(self) -> tide::Result<tide::Response> {
-        Response::ok().json(self.0).map(|r| r.into_inner())
+        self.to_vec().into_response()
     }
 }
 
-/// A Wrapper to return Form data. This can be wrapped over any `serde::Serialize` type.
-pub struct Form<T: Serialize>(pub T);
+/// Returns the UUID in hyphenated form as a plain text body (requires the `uuid` feature)
+/// ```
+/// use hightide::Responder;
+/// use uuid::Uuid;
+///
+/// # async_std::task::block_on(async {
+/// let id = Uuid::from_u128(0x936da01f_9abd_4d9d_80c7_02af85c822a8);
+/// let mut resp = id.into_response().unwrap();
+/// assert_eq!(resp.content_type().unwrap().essence(), "text/plain");
+/// assert_eq!(
+///     resp.take_body().into_string().await.unwrap(),
+///     "936da01f-9abd-4d9d-80c7-02af85c822a8"
+/// );
+/// # });
+/// ```
+#[cfg(feature = "uuid")]
+impl Responder for uuid::Uuid {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Ok(Response::ok()
+            .text(self.hyphenated().to_string())
+            .into_inner())
+    }
+}
 
-impl<T: Serialize> Responder for Form<T> {
+/// Returns the body with status code OK (200), keeping the content type set on the body
+/// ```
+/// use hightide::Responder;
+/// use tide::Body;
+///
+/// let mut body = Body::from_reader(async_std::io::Cursor::new("a,b\n1,2\n"), None);
+/// body.set_mime("text/csv");
+/// let resp = body.into_response().unwrap();
+/// assert_eq!(resp.status(), tide::StatusCode::Ok);
+/// assert_eq!(resp["Content-Type"], "text/csv");
+/// ```
+impl Responder for Body {
     fn into_response(self) -> tide::Result<tide::Response> {
-        Response::ok().form(self.0).map(|r| r.into_inner())
+        Ok(tide::Response::from(self))
     }
 }
 
-impl Responder for Response {
+/// Can never be constructed, this allows `Infallible` to be used where generic code expects a
+/// `Responder`. `Result<R, Infallible>` is also a `Responder`, since `Infallible` implements
+/// `ResponderError`.
+/// ```
+/// use hightide::Responder;
+/// use std::convert::Infallible;
+///
+/// let ok: Result<&str, Infallible> = Ok("always works");
+/// assert!(ok.into_response().is_ok());
+/// ```
+impl Responder for Infallible {
     fn into_response(self) -> tide::Result<tide::Response> {
-        Ok(self.into_inner())
+        match self {}
     }
 }
 
-impl Responder for tide::Response {
+/// Returns an empty response with status code No Content (204)
+///
+/// The body is empty, which makes this convenient for handlers that are only run for their
+/// side effects. No `Content-Type` or `Content-Length` headers are set on the `tide::Response`,
+/// but the transport may still add them when the response is sent (tide's HTTP/1.1 server
+/// `async-h1` sends `Content-Length: 0`).
+/// ```
+/// use hightide::Responder;
+/// use tide::{Request, StatusCode};
+/// async fn delete_thing(_: Request<()>) -> tide::Result<()> {
+///     // ... delete the thing
+///     Ok(())
+/// }
+///
+/// let resp = ().into_response().unwrap();
+/// assert_eq!(resp.status(), StatusCode::NoContent);
+/// assert!(resp.header("Content-Type").is_none());
+/// ```
+impl Responder for () {
     fn into_response(self) -> tide::Result<tide::Response> {
-        Ok(self)
+        StatusCode::NoContent.into_response()
     }
 }
 
-impl<R: Responder> Responder for tide::Result<R> {
+impl<R: Responder> Responder for (StatusCode, R) {
     fn into_response(self) -> tide::Result<tide::Response> {
-        self.and_then(|r| r.into_response())
+        let mut resp = self.1.into_response()?;
+        resp.set_status(self.0);
+        Ok(resp)
     }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        let mut resp = self.1.into_response_for(req)?;
+        resp.set_status(self.0);
+        Ok(resp)
+    }
+}
+
+/// Returns the string with the content type, a lighter alternative to `WithMime` for string
+/// bodies
+///
+/// The tuple impls are told apart by the type of the first element, so this never conflicts
+/// with `(StatusCode, R)`. To set a status as well nest the tuples, as in the example.
+/// ```
+/// use hightide::Responder;
+/// use tide::http::Mime;
+/// use tide::StatusCode;
+///
+/// # fn main() -> tide::Result<()> {
+/// let csv: Mime = "text/csv".parse()?;
+/// let resp = (csv.clone(), "id,name\n1,steve\n".to_owned()).into_response()?;
+/// assert_eq!(resp["Content-Type"], "text/csv");
+///
+/// let resp = (StatusCode::Created, (csv, "id\n2\n".to_owned())).into_response()?;
+/// assert_eq!(resp.status(), StatusCode::Created);
+/// assert_eq!(resp["Content-Type"], "text/csv");
+/// # Ok(())
+/// # }
+/// ```
+impl Responder for (Mime, String) {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Ok(Response::ok()
+            .body(self.1)
+            .content_type(self.0)
+            .into_inner())
+    }
+}
+
+/// A response with a status code and an optional body, a named version of `(StatusCode, R)`
+///
+/// The status replaces the status of the body's response, so it takes priority over statuses
+/// set by the body (like the 204 from `()`). Without a body the response is empty.
+/// ```
+/// use hightide::{Json, Responder, Status};
+/// use tide::StatusCode;
+///
+/// let resp = Status::with(StatusCode::Created, Json(vec![1, 2, 3])).into_response().unwrap();
+/// assert_eq!(resp.status(), StatusCode::Created);
+/// assert_eq!(resp["Content-Type"], "application/json");
+///
+/// let resp = Status::empty(StatusCode::Accepted).into_response().unwrap();
+/// assert_eq!(resp.status(), StatusCode::Accepted);
+/// assert!(resp.is_empty().unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Status<T = ()>(pub StatusCode, pub Option<T>);
+
+impl Status {
+    /// A response with the status code and no body
+    pub fn empty(code: StatusCode) -> Self {
+        Status(code, None)
+    }
+}
+
+impl<T> Status<T> {
+    /// A response with the status code and a body
+    pub fn with(code: StatusCode, body: T) -> Self {
+        Status(code, Some(body))
+    }
+}
+
+impl<T: Responder> Responder for Status<T> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        match self.1 {
+            Some(body) => (self.0, body).into_response(),
+            None => self.0.into_response(),
+        }
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        match self.1 {
+            Some(body) => (self.0, body).into_response_for(req),
+            None => self.0.into_response(),
+        }
+    }
+}
+
+/// Returns the response with the status code and headers replaced
+///
+/// The headers can be any collection of name and value pairs, such as an array or `Vec` of
+/// `(&str, &str)` or `(HeaderName, HeaderValue)`, the same as `Response::headers`. An invalid
+/// header value, including one containing CR or LF, causes `into_response` to return an error.
+/// ```
+/// use hightide::{Json, Responder};
+/// use tide::StatusCode;
+///
+/// let resp = (StatusCode::Created, [("Location", "/users/42")], Json(vec![42]))
+///     .into_response()
+///     .unwrap();
+/// assert_eq!(resp.status(), StatusCode::Created);
+/// assert_eq!(resp["Location"], "/users/42");
+/// assert_eq!(resp["Content-Type"], "application/json");
+///
+/// let bad = [("X-Bad", "a\r\nSet-Cookie: evil=1")];
+/// assert!((StatusCode::Ok, bad, "body").into_response().is_err());
+/// ```
+impl<I, N, V, R> Responder for (StatusCode, I, R)
+where
+    I: IntoIterator<Item = (N, V)>,
+    N: Into<HeaderName>,
+    V: ToHeaderValues,
+    R: Responder,
+{
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let resp = self.2.into_response()?;
+        Response::from(resp)
+            .with_status(self.0)
+            .headers(self.1)
+            .map(|r| r.into_inner())
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        let resp = self.2.into_response_for(req)?;
+        Response::from(resp)
+            .with_status(self.0)
+            .headers(self.1)
+            .map(|r| r.into_inner())
+    }
+}
+
+/// Returns `StatusCode::NotFound` for `None`, and the inner value for `Some`
+///
+/// The `None` case produces an empty body, so a handler that looks up a missing resource
+/// can simply return the lookup result:
+///
+/// ```
+/// use hightide::Responder;
+/// use tide::Request;
+/// fn find_user(id: u32) -> Option<String> {
+///     None
+/// }
+///
+/// fn example(_: Request<()>) -> impl Responder {
+///     // a 404 if the user doesn't exist
+///     find_user(42)
+/// }
+/// ```
+impl<R: Responder> Responder for Option<R> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        match self {
+            None => StatusCode::NotFound.into_response(),
+            Some(r) => r.into_response(),
+        }
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        match self {
+            None => StatusCode::NotFound.into_response(),
+            Some(r) => r.into_response_for(req),
+        }
+    }
+}
+
+/// One of two responders, for endpoints which return different types from different branches
+/// ```
+/// use hightide::{Either, Json, Responder};
+/// use tide::{Request, StatusCode};
+///
+/// fn example(req: Request<()>) -> impl Responder {
+///     if req.url().query().is_some() {
+///         Either::Left(Json(vec![1, 2, 3]))
+///     } else {
+///         Either::Right((StatusCode::BadRequest, "missing query"))
+///     }
+/// }
+///
+/// let resp = Either::<Json<Vec<u32>>, _>::Right(StatusCode::Gone).into_response().unwrap();
+/// assert_eq!(resp.status(), StatusCode::Gone);
+/// ```
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<L: Responder, R: Responder> Responder for Either<L, R> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        match self {
+            Either::Left(l) => l.into_response(),
+            Either::Right(r) => r.into_response(),
+        }
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        match self {
+            Either::Left(l) => l.into_response_for(req),
+            Either::Right(r) => r.into_response_for(req),
+        }
+    }
+}
+
+/// A Wrapper to return a JSON payload. This can be wrapped over any `serde::Serialize` type.
+/// ```
+/// use tide::Request;
+/// use hightide::{Responder, Json};
+/// fn returns_json(_: Request<()>) -> impl Responder {
+///     Json(vec!["an", "array"])
+/// }
+/// ```
+pub struct Json<T: Serialize>(pub T);
+
+impl<T: Serialize> Responder for Json<T> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Response::ok().json(self.0).map(|r| r.into_inner())
+    }
+}
+
+/// A Wrapper to return a pretty-printed JSON payload. This can be wrapped over any
+/// `serde::Serialize` type.
+/// ```
+/// use hightide::{JsonPretty, Responder};
+///
+/// # async_std::task::block_on(async {
+/// let mut resp = JsonPretty(vec![1, 2]).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "application/json");
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "[\n  1,\n  2\n]");
+/// # });
+/// ```
+pub struct JsonPretty<T: Serialize>(pub T);
+
+impl<T: Serialize> Responder for JsonPretty<T> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Response::ok().json_pretty(self.0).map(|r| r.into_inner())
+    }
+}
+
+/// Returns the value as a JSON payload, the same as `Json(value)`
+/// ```
+/// use hightide::Responder;
+/// use tide::convert::json;
+/// use tide::Request;
+/// fn returns_json(_: Request<()>) -> impl Responder {
+///     json!({ "id": 42, "tags": ["a", "b"] })
+/// }
+/// ```
+impl Responder for serde_json::Value {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Json(self).into_response()
+    }
+}
+
+/// Returns the map as a JSON object, the same as `Json(map)`
+/// ```
+/// use hightide::Responder;
+/// use serde_json::{Map, Value};
+///
+/// let mut map = Map::new();
+/// map.insert("id".to_owned(), Value::from(42));
+/// let resp = map.into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "application/json");
+/// ```
+impl Responder for serde_json::Map<String, serde_json::Value> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Json(self).into_response()
+    }
+}
+
+/// Returns the map as a JSON object, the same as `Json(map)`. Maps are always sent as JSON, use
+/// `Form` for form data.
+///
+/// JSON object keys must be strings, so keys which don't serialize as a string or number return
+/// an error.
+/// ```
+/// use hightide::Responder;
+/// use std::collections::HashMap;
+///
+/// let mut counts = HashMap::new();
+/// counts.insert("apples", 3);
+/// let resp = counts.into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "application/json");
+/// ```
+impl<K: Serialize, V: Serialize, H> Responder for HashMap<K, V, H> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Json(self).into_response()
+    }
+}
+
+/// Returns the map as a JSON object, the same as `Json(map)`, with the keys in order
+impl<K: Serialize, V: Serialize> Responder for BTreeMap<K, V> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Json(self).into_response()
+    }
+}
+
+/// A Wrapper to return Form data. This can be wrapped over any `serde::Serialize` type.
+pub struct Form<T: Serialize>(pub T);
+
+impl<T: Serialize> Responder for Form<T> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Response::ok().form(self.0).map(|r| r.into_inner())
+    }
+}
+
+/// A Wrapper to return a YAML payload (requires the `yaml` feature). This can be wrapped over
+/// any `serde::Serialize` type.
+/// ```
+/// use tide::Request;
+/// use hightide::{Responder, Yaml};
+/// fn returns_yaml(_: Request<()>) -> impl Responder {
+///     Yaml(vec!["an", "array"])
+/// }
+///
+/// let resp = Yaml(vec!["an", "array"]).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "application/x-yaml");
+/// ```
+#[cfg(feature = "yaml")]
+pub struct Yaml<T: Serialize>(pub T);
+
+#[cfg(feature = "yaml")]
+impl<T: Serialize> Responder for Yaml<T> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Response::ok().yaml(self.0).map(|r| r.into_inner())
+    }
+}
+
+/// A Wrapper to return a MessagePack payload (requires the `msgpack` feature). This can be
+/// wrapped over any `serde::Serialize` type.
+/// ```
+/// use tide::Request;
+/// use hightide::{Responder, MsgPack};
+/// fn returns_msgpack(_: Request<()>) -> impl Responder {
+///     MsgPack(vec!["an", "array"])
+/// }
+///
+/// let resp = MsgPack(vec!["an", "array"]).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "application/msgpack");
+/// ```
+#[cfg(feature = "msgpack")]
+pub struct MsgPack<T: Serialize>(pub T);
+
+#[cfg(feature = "msgpack")]
+impl<T: Serialize> Responder for MsgPack<T> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Response::ok().msgpack(self.0).map(|r| r.into_inner())
+    }
+}
+
+/// A Wrapper to return a CBOR payload (requires the `cbor` feature). This can be wrapped over
+/// any `serde::Serialize` type.
+/// ```
+/// use hightide::{Cbor, Responder};
+///
+/// # async_std::task::block_on(async {
+/// let mut resp = Cbor(vec![1, 2]).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "application/cbor");
+/// assert_eq!(resp.take_body().into_bytes().await.unwrap(), [0x82, 0x01, 0x02]);
+/// # });
+/// ```
+#[cfg(feature = "cbor")]
+pub struct Cbor<T: Serialize>(pub T);
+
+#[cfg(feature = "cbor")]
+impl<T: Serialize> Responder for Cbor<T> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Response::ok().cbor(self.0).map(|r| r.into_inner())
+    }
+}
+
+/// A Wrapper to return an XML payload (requires the `xml` feature). This can be wrapped over
+/// any `serde::Serialize` type, see `Response::xml` for the limitations.
+/// ```
+/// use hightide::{Responder, Xml};
+///
+/// #[derive(serde::Serialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// # async_std::task::block_on(async {
+/// let mut resp = Xml(User { name: "ferris".to_owned() }).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "application/xml;charset=utf-8");
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "<User><name>ferris</name></User>");
+///
+/// assert!(Xml(vec![1, 2, 3]).into_response().is_err());
+/// # });
+/// ```
+#[cfg(feature = "xml")]
+pub struct Xml<T: Serialize>(pub T);
+
+#[cfg(feature = "xml")]
+impl<T: Serialize> Responder for Xml<T> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Response::ok().xml(self.0).map(|r| r.into_inner())
+    }
+}
+
+/// A redirect to another location, setting the `Location` header and a 3xx status code
+///
+/// The location is placed into the header verbatim. If it is not a valid header value
+/// (for example it contains non-ASCII characters) then `into_response` returns an error.
+/// ```
+/// use tide::{Request, StatusCode};
+/// use hightide::{Responder, Redirect};
+/// fn login(_: Request<()>) -> impl Responder {
+///     Redirect::see_other("/login")
+/// }
+///
+/// let resp = Redirect::see_other("/login").into_response().unwrap();
+/// assert_eq!(resp.status(), StatusCode::SeeOther);
+/// assert_eq!(resp["Location"], "/login");
+///
+/// assert!(Redirect::temporary("/caf\u{e9}").into_response().is_err());
+/// ```
+pub struct Redirect {
+    status: StatusCode,
+    location: String,
+}
+
+impl Redirect {
+    fn new(status: StatusCode, location: impl AsRef<str>) -> Self {
+        Self {
+            status,
+            location: location.as_ref().to_owned(),
+        }
+    }
+
+    /// Redirect with status code See Other (303), the client should follow with a GET request
+    pub fn see_other(location: impl AsRef<str>) -> Self {
+        Self::new(StatusCode::SeeOther, location)
+    }
+
+    /// Redirect with status code Temporary Redirect (307)
+    pub fn temporary(location: impl AsRef<str>) -> Self {
+        Self::new(StatusCode::TemporaryRedirect, location)
+    }
+
+    /// Redirect with status code Permanent Redirect (308)
+    pub fn permanent(location: impl AsRef<str>) -> Self {
+        Self::new(StatusCode::PermanentRedirect, location)
+    }
+}
+
+impl Responder for Redirect {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let location = location_value(&self.location)?;
+        let mut resp = tide::Response::new(self.status);
+        resp.insert_header(headers::LOCATION, location);
+        Ok(resp)
+    }
+}
+
+/// A resource was created, this sets the status to Created (201) and the `Location` header to
+/// point at the new resource. The body is taken from the wrapped responder.
+///
+/// As with `Redirect` an invalid location causes `into_response` to return an error.
+/// ```
+/// use tide::{Request, StatusCode};
+/// use hightide::{Responder, Created, Json};
+/// fn create(_: Request<()>) -> impl Responder {
+///     Created::at("/users/42", Json(vec![42]))
+/// }
+///
+/// let resp = Created::at("/users/42", "created").into_response().unwrap();
+/// assert_eq!(resp.status(), StatusCode::Created);
+/// assert_eq!(resp["Location"], "/users/42");
+/// ```
+pub struct Created<R: Responder> {
+    location: String,
+    body: R,
+}
+
+impl<R: Responder> Created<R> {
+    /// Create the response with the location of the new resource and the response body
+    pub fn at(location: impl AsRef<str>, body: R) -> Self {
+        Self {
+            location: location.as_ref().to_owned(),
+            body,
+        }
+    }
+}
+
+impl<R: Responder> Responder for Created<R> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let location = location_value(&self.location)?;
+        let mut resp = self.body.into_response()?;
+        resp.set_status(StatusCode::Created);
+        resp.insert_header(headers::LOCATION, location);
+        Ok(resp)
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        let location = location_value(&self.location)?;
+        let mut resp = self.body.into_response_for(req)?;
+        resp.set_status(StatusCode::Created);
+        resp.insert_header(headers::LOCATION, location);
+        Ok(resp)
+    }
+}
+
+/// A Wrapper to return an HTML payload. This can be wrapped over any type that converts into a `String`.
+/// ```
+/// use tide::Request;
+/// use hightide::{Responder, Html};
+/// fn returns_html(_: Request<()>) -> impl Responder {
+///     Html("<h1>hi</h1>")
+/// }
+/// ```
+pub struct Html<T: Into<String>>(pub T);
+
+impl<T: Into<String>> Responder for Html<T> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Ok(Response::ok().html(self.0).into_inner())
+    }
+}
+
+/// A Wrapper to return any body with a content type, the body can be anything which converts
+/// into a `tide::Body` such as a `String` or `Vec<u8>`
+///
+/// String content types are parsed into a `Mime`, which panics if they are not a valid media type
+/// ```
+/// use hightide::{Responder, WithMime};
+///
+/// let svg = String::from("<svg/>");
+/// let resp = WithMime("image/svg+xml", svg).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "image/svg+xml");
+/// ```
+pub struct WithMime<M, B>(pub M, pub B);
+
+impl<M: Into<Mime>, B: Into<Body>> Responder for WithMime<M, B> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Ok(Response::ok()
+            .body(self.1)
+            .content_type(self.0)
+            .into_inner())
+    }
+}
+
+/// A Wrapper to return raw bytes with a content type, the bytes can be anything which converts
+/// into a `Vec<u8>`
+///
+/// String content types are parsed into a `Mime`, which panics if they are not a valid media type.
+/// This is not the same as `bytes::Bytes`, which is sent as `application/octet-stream`.
+/// ```
+/// use hightide::{Bytes, Responder};
+///
+/// let png: Vec<u8> = vec![0x89, b'P', b'N', b'G'];
+/// let resp = Bytes("image/png", png).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "image/png");
+/// assert_eq!(resp.len(), Some(4));
+/// ```
+pub struct Bytes<M, D>(pub M, pub D);
+
+impl<M: Into<Mime>, D: Into<Vec<u8>>> Responder for Bytes<M, D> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let mut body = Body::from_bytes(self.1.into());
+        body.set_mime(self.0);
+        Ok(tide::Response::from(body))
+    }
+}
+
+/// A Wrapper to return a duration as a plain text number of milliseconds, sub-millisecond parts
+/// are truncated
+/// ```
+/// use hightide::{Elapsed, Responder};
+/// use std::time::Duration;
+///
+/// # async_std::task::block_on(async {
+/// let mut resp = Elapsed(Duration::from_micros(1_500_900)).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "text/plain;charset=utf-8");
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "1500");
+/// # });
+/// ```
+pub struct Elapsed(pub Duration);
+
+impl Responder for Elapsed {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Ok(Response::ok()
+            .text(self.0.as_millis().to_string())
+            .into_inner())
+    }
+}
+
+/// A Wrapper to return plain text which is formatted lazily, the closure is only called when
+/// the response is built
+///
+/// The content type is `text/plain;charset=utf-8`, the same as `String`. This defers
+/// formatting a body which is expensive to produce until the response is actually built.
+/// ```
+/// use hightide::{Fmt, Responder};
+///
+/// # async_std::task::block_on(async {
+/// let items = vec![1, 2, 3];
+/// let mut resp = Fmt(move || format!("{} items", items.len())).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "text/plain;charset=utf-8");
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "3 items");
+/// # });
+/// ```
+pub struct Fmt<F: FnOnce() -> String>(pub F);
+
+impl<F: FnOnce() -> String> Responder for Fmt<F> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Ok(Response::ok().text((self.0)()).into_inner())
+    }
+}
+
+impl Responder for Response {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Ok(self.into_inner())
+    }
+}
+
+/// Build a response from a simple value, the same as returning it from an endpoint
+///
+/// Only conversions which can't fail are provided, so there is no `From<(StatusCode, R)>` for
+/// every responder - use `Responder::into_response` for those.
+/// ```
+/// use hightide::Response;
+/// use tide::StatusCode;
+///
+/// fn not_found() -> Response {
+///     Response::from((StatusCode::NotFound, "no such user"))
+/// }
+///
+/// let resp = not_found().into_inner();
+/// assert_eq!(resp.status(), StatusCode::NotFound);
+/// assert_eq!(resp["Content-Type"], "text/plain;charset=utf-8");
+///
+/// let resp: Response = StatusCode::Accepted.into();
+/// assert_eq!(resp.into_inner().status(), StatusCode::Accepted);
+/// ```
+impl From<&str> for Response {
+    fn from(s: &str) -> Self {
+        Self::from(tide::Response::from(s))
+    }
+}
+
+impl From<String> for Response {
+    fn from(s: String) -> Self {
+        Self::from(tide::Response::from(s))
+    }
+}
+
+impl From<StatusCode> for Response {
+    fn from(s: StatusCode) -> Self {
+        Self::status(s)
+    }
+}
+
+impl From<(StatusCode, &str)> for Response {
+    fn from((s, body): (StatusCode, &str)) -> Self {
+        Self::status(s).body(body)
+    }
+}
+
+impl From<(StatusCode, String)> for Response {
+    fn from((s, body): (StatusCode, String)) -> Self {
+        Self::status(s).body(body)
+    }
+}
+
+/// Sends trailers for a `Response` after it has been returned, created with
+/// `Response::trailer_sender`
+pub struct TrailerSender {
+    sender: trailers::Sender,
+    trailers: Trailers,
+}
+
+impl TrailerSender {
+    /// Send the trailers, along with any added to the response before the sender was taken
+    pub async fn send(mut self, trailers: Trailers) {
+        for (name, values) in trailers.iter() {
+            self.trailers.insert(name.clone(), values);
+        }
+        self.sender.send(self.trailers).await
+    }
+}
+
+impl From<tide::Response> for Response {
+    fn from(inner: tide::Response) -> Self {
+        Self {
+            inner,
+            trailers: None,
+        }
+    }
+}
+
+impl Responder for tide::Response {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Ok(self)
+    }
+}
+
+impl<R: Responder> Responder for tide::Result<R> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        self.and_then(|r| r.into_response())
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        self.and_then(|r| r.into_response_for(req))
+    }
+}
+
+/// Returns the response for `Ok`, and for `Err` an error with status Internal Server Error (500)
+/// and the error's message (requires the `anyhow` feature)
+/// ```
+/// use hightide::Responder;
+/// use tide::{Request, StatusCode};
+///
+/// fn lookup(_: Request<()>) -> anyhow::Result<String> {
+///     anyhow::bail!("database unavailable")
+/// }
+///
+/// let err: anyhow::Result<String> = Err(anyhow::anyhow!("database unavailable"));
+/// let err = err.into_response().unwrap_err();
+/// assert_eq!(err.status(), StatusCode::InternalServerError);
+/// assert_eq!(err.to_string(), "database unavailable");
+/// ```
+#[cfg(feature = "anyhow")]
+impl<R: Responder> Responder for anyhow::Result<R> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        self.map_err(anyhow_error).and_then(|r| r.into_response())
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        self.map_err(anyhow_error)
+            .and_then(|r| r.into_response_for(req))
+    }
+}
+
+#[cfg(feature = "anyhow")]
+fn anyhow_error(err: anyhow::Error) -> tide::Error {
+    tide::Error::new(StatusCode::InternalServerError, err)
 }
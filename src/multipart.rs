@@ -0,0 +1,143 @@
+use crate::request_id::random_bytes;
+use crate::Responder;
+use std::fmt::Write;
+use tide::http::headers::{self, HeaderName};
+use tide::{Body, StatusCode};
+
+/// A single part of a `Multipart` response
+/// ```
+/// use hightide::MultipartPart;
+///
+/// let part = MultipartPart::new()
+///     .header("Content-Type", "application/json")
+///     .body(r#"{"id": 1}"#);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MultipartPart {
+    headers: Vec<(HeaderName, String)>,
+    body: Vec<u8>,
+}
+
+impl MultipartPart {
+    /// Create a part with no headers and an empty body
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a header to the part
+    pub fn header(mut self, name: impl Into<HeaderName>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set the body of the part
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+}
+
+/// A responder for a `multipart/mixed` body, such as the response to a batch request
+///
+/// The boundary is `hightide-` followed by 32 random hex digits, and is regenerated if it
+/// happens to appear in one of the part bodies. The boundary only contains letters, digits and
+/// `-` so it never needs quoting in the `Content-Type` header. Part bodies are written as they
+/// are, header values can't be escaped so a value containing a line break or non ASCII
+/// characters returns an Internal Server Error (500).
+/// ```
+/// use hightide::{Multipart, MultipartPart, Responder};
+///
+/// # async_std::task::block_on(async {
+/// let mut resp = Multipart::new(vec![
+///     MultipartPart::new().header("Content-Type", "text/plain").body("first"),
+///     MultipartPart::new().body("second"),
+/// ])
+/// .into_response()
+/// .unwrap();
+///
+/// let content_type = resp["Content-Type"].as_str().to_owned();
+/// let boundary = content_type.strip_prefix("multipart/mixed; boundary=").unwrap();
+/// let body = resp.take_body().into_string().await.unwrap();
+/// assert_eq!(body, format!(
+///     "--{b}\r\ncontent-type: text/plain\r\n\r\nfirst\r\n--{b}\r\n\r\nsecond\r\n--{b}--\r\n",
+///     b = boundary,
+/// ));
+///
+/// let bad = Multipart::new(vec![MultipartPart::new().header("X-Bad", "a\r\nb")]);
+/// assert!(bad.into_response().is_err());
+/// # });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Multipart {
+    parts: Vec<MultipartPart>,
+}
+
+impl Multipart {
+    /// Create a multipart body from a collection of parts
+    pub fn new(parts: impl IntoIterator<Item = MultipartPart>) -> Self {
+        Self {
+            parts: parts.into_iter().collect(),
+        }
+    }
+
+    /// Add another part
+    pub fn part(mut self, part: MultipartPart) -> Self {
+        self.parts.push(part);
+        self
+    }
+}
+
+impl Responder for Multipart {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let boundary = loop {
+            let boundary = generate_boundary();
+            if !self
+                .parts
+                .iter()
+                .any(|part| contains(&part.body, &boundary))
+            {
+                break boundary;
+            }
+        };
+
+        let mut body = Vec::new();
+        for part in self.parts {
+            body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+            for (name, value) in part.headers {
+                if !value.is_ascii() || value.contains(&['\r', '\n'][..]) {
+                    return Err(tide::Error::from_str(
+                        StatusCode::InternalServerError,
+                        format!("invalid value for multipart header {}", name),
+                    ));
+                }
+                body.extend_from_slice(format!("{}: {}\r\n", name, value).as_bytes());
+            }
+            body.extend_from_slice(b"\r\n");
+            body.extend_from_slice(&part.body);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+
+        let mut resp = tide::Response::new(StatusCode::Ok);
+        resp.set_body(Body::from_bytes(body));
+        resp.insert_header(
+            headers::CONTENT_TYPE,
+            format!("multipart/mixed; boundary={}", boundary),
+        );
+        Ok(resp)
+    }
+}
+
+fn generate_boundary() -> String {
+    let mut boundary = String::from("hightide-");
+    for b in random_bytes().iter() {
+        write!(boundary, "{:02x}", b).unwrap();
+    }
+    boundary
+}
+
+fn contains(haystack: &[u8], needle: &str) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle.as_bytes())
+}
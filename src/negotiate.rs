@@ -0,0 +1,106 @@
+use crate::vary::append_vary;
+use crate::{Responder, Response};
+use tide::convert::Serialize;
+use tide::http::content::{Accept, MediaTypeProposal};
+use tide::http::{self, mime, Mime};
+use tide::StatusCode;
+
+/// A Wrapper to return either a JSON or Form payload, chosen using the request's `Accept` header.
+/// This can be wrapped over any `serde::Serialize` type.
+///
+/// The type with the higher weight in the `Accept` header is used, taking each weight from the
+/// most specific media range that matches, so a range with `q=0` excludes that type even when a
+/// wildcard is also present. JSON is preferred when the client accepts both equally, or when the
+/// request has no `Accept` header. If the client accepts neither then the response is Not
+/// Acceptable (406). A `Vary: Accept`
+/// header is added so that caches keep the representations apart.
+///
+/// The request is only available when returned from an endpoint wrapped with `wrap` or one of
+/// the other `wrap_*` functions, otherwise (for example when calling `into_response` directly)
+/// JSON is always used.
+/// ```
+/// use hightide::{Negotiate, Responder};
+/// use tide::http::{Method, Request};
+/// use tide::StatusCode;
+///
+/// let mut req = Request::new(Method::Get, "http://example.com/");
+/// req.insert_header("Accept", "application/x-www-form-urlencoded");
+///
+/// let resp = Negotiate(vec![("key", "value")]).into_response_for(&req).unwrap();
+/// assert_eq!(resp["Content-Type"], "application/x-www-form-urlencoded");
+///
+/// req.insert_header("Accept", "application/json;q=0, */*");
+/// let resp = Negotiate(vec![("key", "value")]).into_response_for(&req).unwrap();
+/// assert_eq!(resp["Content-Type"], "application/x-www-form-urlencoded");
+///
+/// req.insert_header("Accept", "text/html");
+/// let err = Negotiate(vec![("key", "value")]).into_response_for(&req).unwrap_err();
+/// assert_eq!(err.status(), StatusCode::NotAcceptable);
+/// ```
+pub struct Negotiate<T: Serialize>(pub T);
+
+impl<T: Serialize> Responder for Negotiate<T> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        Response::ok().json(self.0).map(|r| r.into_inner())
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        let resp = if accepts_json(req)? {
+            Response::ok().json(self.0)?
+        } else {
+            Response::ok().form(self.0)?
+        };
+        let mut resp = resp.into_inner();
+        append_vary(&mut resp, &["Accept"]);
+        Ok(resp)
+    }
+}
+
+/// Decide between JSON (true) and Form (false), or fail with Not Acceptable
+fn accepts_json(req: &http::Request) -> tide::Result<bool> {
+    let accept = match Accept::from_headers(req) {
+        Ok(Some(accept)) => accept,
+        Ok(None) => return Ok(true),
+        Err(mut err) => {
+            err.set_status(StatusCode::BadRequest);
+            return Err(err);
+        }
+    };
+
+    let json = quality(&accept, &mime::JSON);
+    let form = quality(&accept, &mime::FORM);
+    if json > 0.0 && json >= form {
+        return Ok(true);
+    }
+    if form > 0.0 {
+        return Ok(false);
+    }
+
+    Err(tide::Error::from_str(
+        StatusCode::NotAcceptable,
+        "No acceptable content type, expected application/json or application/x-www-form-urlencoded",
+    ))
+}
+
+/// The weight the `Accept` header gives a mime type, taken from the most specific media range
+/// which matches it, so `application/json;q=0` excludes JSON even with `*/*`. A mime type with
+/// no matching range has weight 0 (not acceptable).
+fn quality(accept: &Accept, mime: &Mime) -> f32 {
+    accept
+        .iter()
+        .filter(|proposal| matches(proposal, mime))
+        .max_by_key(|proposal| specificity(proposal))
+        .map(|proposal| proposal.weight().unwrap_or(1.0))
+        .unwrap_or(if accept.wildcard() { 1.0 } else { 0.0 })
+}
+
+/// How specific a media range is, `type/subtype` beats `type/*` which beats `*/*`
+fn specificity(proposal: &MediaTypeProposal) -> u8 {
+    (proposal.basetype() != "*") as u8 + (proposal.subtype() != "*") as u8
+}
+
+/// Check if a proposal from the `Accept` header matches a mime type, including wildcards
+fn matches(proposal: &MediaTypeProposal, mime: &Mime) -> bool {
+    (proposal.basetype() == "*" || proposal.basetype() == mime.basetype())
+        && (proposal.subtype() == "*" || proposal.subtype() == mime.subtype())
+}
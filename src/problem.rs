@@ -0,0 +1,158 @@
+use crate::{Responder, ResponderError};
+use serde_json::{Map, Value};
+use tide::convert::Serialize;
+use tide::{Body, StatusCode};
+
+/// An RFC 7807 problem details response, with content type `application/problem+json`
+///
+/// The `status` member is always included and `title` defaults to the canonical reason for the
+/// status code. The other members are only included when set. Extension members are added
+/// alongside the standard ones, but can't replace them.
+///
+/// `Problem` also implements `ResponderError` so it can be used as the error type of an endpoint.
+/// ```
+/// use hightide::{Json, Problem, Responder};
+/// use tide::{Request, StatusCode};
+///
+/// fn withdraw(_: Request<()>) -> Result<Json<u32>, Problem> {
+///     Err(Problem::new(StatusCode::Forbidden)
+///         .type_uri("https://example.com/probs/out-of-credit")
+///         .title("You do not have enough credit.")
+///         .detail("Your current balance is 30, but that costs 50.")
+///         .instance("/account/12345/msgs/abc")
+///         .extension("balance", 30))
+/// }
+///
+/// let resp = Problem::new(StatusCode::NotFound).into_response().unwrap();
+/// assert_eq!(resp.status(), StatusCode::NotFound);
+/// assert_eq!(resp["Content-Type"], "application/problem+json");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Problem {
+    status: StatusCode,
+    type_uri: Option<String>,
+    title: Option<String>,
+    detail: Option<String>,
+    instance: Option<String>,
+    extensions: Map<String, Value>,
+    error: Option<String>,
+}
+
+impl Problem {
+    /// Create a problem with the given status code
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            status,
+            type_uri: None,
+            title: None,
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+            error: None,
+        }
+    }
+
+    /// Set the URI identifying the problem type (`type` member)
+    pub fn type_uri(mut self, type_uri: impl Into<String>) -> Self {
+        self.type_uri = Some(type_uri.into());
+        self
+    }
+
+    /// Set the short human readable summary of the problem type
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the human readable explanation of this occurrence of the problem
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Set the URI identifying this occurrence of the problem
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Add an extension member, the value can be any `serde::Serialize` type
+    ///
+    /// If the value fails to serialize the error is returned from `into_response` as an
+    /// Internal Server Error (500).
+    /// ```
+    /// use hightide::{Problem, Responder};
+    /// use tide::StatusCode;
+    ///
+    /// #[derive(serde::Serialize)]
+    /// struct Field {
+    ///     name: &'static str,
+    ///     reason: &'static str,
+    /// }
+    ///
+    /// # async_std::task::block_on(async {
+    /// let problem = Problem::new(StatusCode::BadRequest)
+    ///     .extension("invalid_params", vec![Field { name: "age", reason: "must be positive" }]);
+    /// let mut resp = problem.into_response().unwrap();
+    /// let body: serde_json::Value = resp.take_body().into_json().await.unwrap();
+    /// assert_eq!(body["invalid_params"][0]["name"], "age");
+    /// # });
+    /// ```
+    pub fn extension(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
+        match serde_json::to_value(value) {
+            Ok(value) => {
+                self.extensions.insert(key.into(), value);
+            }
+            Err(err) => {
+                self.error.get_or_insert(err.to_string());
+            }
+        }
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let mut obj = self.extensions.clone();
+        if let Some(type_uri) = &self.type_uri {
+            obj.insert("type".to_owned(), type_uri.as_str().into());
+        }
+        let title = match &self.title {
+            Some(title) => title.as_str(),
+            None => self.status.canonical_reason(),
+        };
+        obj.insert("title".to_owned(), title.into());
+        obj.insert("status".to_owned(), u16::from(self.status).into());
+        if let Some(detail) = &self.detail {
+            obj.insert("detail".to_owned(), detail.as_str().into());
+        }
+        if let Some(instance) = &self.instance {
+            obj.insert("instance".to_owned(), instance.as_str().into());
+        }
+        Value::Object(obj)
+    }
+}
+
+impl Responder for Problem {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        if let Some(err) = self.error {
+            return Err(tide::Error::from_str(
+                StatusCode::InternalServerError,
+                format!("invalid problem extension: {}", err),
+            ));
+        }
+        let mut body = Body::from_json(&self.to_json())?;
+        body.set_mime("application/problem+json");
+        let mut resp = tide::Response::new(self.status);
+        resp.set_body(body);
+        Ok(resp)
+    }
+}
+
+impl ResponderError for Problem {
+    fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    fn into_error_response(self) -> tide::Result<tide::Response> {
+        self.into_response()
+    }
+}
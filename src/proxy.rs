@@ -0,0 +1,105 @@
+use crate::Response;
+#[cfg(feature = "compression")]
+use async_compression::futures::bufread::{BrotliDecoder, GzipDecoder};
+#[cfg(feature = "compression")]
+use futures::io::BufReader;
+use tide::http;
+#[cfg(feature = "compression")]
+use tide::{http::headers, Body};
+
+/// Headers which only apply to a single connection and must not be forwarded by a proxy
+const HOP_BY_HOP: &[&str] = &[
+    "Connection",
+    "Keep-Alive",
+    "Proxy-Authenticate",
+    "Proxy-Authorization",
+    "TE",
+    "Trailer",
+    "Transfer-Encoding",
+    "Upgrade",
+];
+
+impl Response {
+    /// Build a response from an upstream response, for proxies and gateways
+    ///
+    /// Anything convertible into an `http_types::Response` can be used, including `surf`
+    /// responses. The status, headers and body are copied, except for the hop-by-hop headers
+    /// (`Connection`, `Transfer-Encoding` etc., and any header named in `Connection`) which only
+    /// apply to the upstream connection. The body is streamed, not read into memory.
+    ///
+    /// With the `compression` feature a `gzip` (or `x-gzip`) or `br` body is decoded, and the
+    /// `Content-Encoding` and `Content-Length` headers are removed. Any other encoding (such as
+    /// `deflate`, `zstd`, or several stacked encodings) and every encoding without the feature
+    /// is passed through unchanged with its `Content-Encoding` header, so the client decodes it.
+    /// ```
+    /// use hightide::Response;
+    /// use tide::http;
+    ///
+    /// # async_std::task::block_on(async {
+    /// let mut upstream = http::Response::new(http::StatusCode::Created);
+    /// upstream.insert_header("Connection", "keep-alive, X-Internal");
+    /// upstream.insert_header("X-Internal", "secret");
+    /// upstream.insert_header("Content-Encoding", "deflate");
+    /// upstream.insert_header("X-Upstream", "1");
+    /// upstream.set_body("compressed bytes");
+    ///
+    /// let mut resp = Response::from_upstream(upstream).into_inner();
+    /// assert_eq!(resp.status(), http::StatusCode::Created);
+    /// assert_eq!(resp["X-Upstream"], "1");
+    /// assert_eq!(resp["Content-Encoding"], "deflate");
+    /// assert!(resp.header("Connection").is_none());
+    /// assert!(resp.header("X-Internal").is_none());
+    /// assert_eq!(resp.take_body().into_string().await.unwrap(), "compressed bytes");
+    /// # });
+    /// ```
+    pub fn from_upstream(upstream: impl Into<http::Response>) -> Self {
+        let mut upstream: http::Response = upstream.into();
+        // headers named in `Connection` are hop-by-hop too
+        let listed = upstream
+            .header("Connection")
+            .map(|values| {
+                values
+                    .iter()
+                    .flat_map(|value| value.as_str().split(','))
+                    .map(|name| name.trim().to_owned())
+                    .filter(|name| !name.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        for name in &listed {
+            upstream.remove_header(name.as_str());
+        }
+        for name in HOP_BY_HOP {
+            upstream.remove_header(*name);
+        }
+
+        #[cfg(feature = "compression")]
+        decode_body(&mut upstream);
+
+        Response::from(tide::Response::from(upstream))
+    }
+}
+
+#[cfg(feature = "compression")]
+fn decode_body(resp: &mut http::Response) {
+    let encoding = match resp.header(headers::CONTENT_ENCODING) {
+        Some(values) if values.iter().count() == 1 => values.as_str().trim().to_ascii_lowercase(),
+        _ => return,
+    };
+
+    let body = resp.take_body();
+    let mime = body.mime().clone();
+    let mut decoded = match encoding.as_str() {
+        "gzip" | "x-gzip" => Body::from_reader(BufReader::new(GzipDecoder::new(body)), None),
+        "br" => Body::from_reader(BufReader::new(BrotliDecoder::new(body)), None),
+        _ => {
+            resp.set_body(body);
+            return;
+        }
+    };
+    decoded.set_mime(mime);
+
+    resp.remove_header(headers::CONTENT_ENCODING);
+    resp.remove_header(headers::CONTENT_LENGTH);
+    resp.set_body(decoded);
+}
@@ -0,0 +1,248 @@
+use crate::language::preferred_language;
+use hyperx::header::{Header, Raw};
+use tide::convert::DeserializeOwned;
+use tide::http::{self, mime, Headers};
+use tide::{Request, StatusCode};
+
+/// Read the request body and parse it as JSON
+///
+/// Unlike `Request::body_json` a body that fails to parse is a Bad Request (400) error, with the
+/// parse error as the message. An empty body is not valid JSON, so it is also a Bad Request.
+/// ```
+/// use hightide::{body_json, Responder};
+/// use tide::Request;
+/// # #[derive(serde::Deserialize)]
+/// # struct NewUser { name: String }
+///
+/// async fn create_user(mut req: Request<()>) -> tide::Result<impl Responder> {
+///     let user: NewUser = body_json(&mut req).await?;
+///     Ok(format!("Hello {}", user.name))
+/// }
+///
+/// # async_std::task::block_on(async {
+/// let mut app = tide::new();
+/// app.at("/users").post(hightide::wrap(create_user));
+///
+/// let mut req = tide::http::Request::new(tide::http::Method::Post, "http://localhost/users");
+/// req.set_body("not json");
+/// let resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp.status(), tide::StatusCode::BadRequest);
+/// # });
+/// ```
+pub async fn body_json<T, State>(req: &mut Request<State>) -> tide::Result<T>
+where
+    T: DeserializeOwned,
+{
+    let bytes = req.body_bytes().await?;
+    serde_json::from_slice(&bytes).map_err(|err| {
+        tide::Error::from_str(
+            StatusCode::BadRequest,
+            format!("invalid JSON body: {}", err),
+        )
+    })
+}
+
+/// Read the request body and parse it as `application/x-www-form-urlencoded` form data
+///
+/// A request with a different `Content-Type` is a Bad Request (400) error, as is a body that
+/// fails to parse. A request without a `Content-Type` header is parsed as form data anyway.
+/// ```
+/// use hightide::{body_form, Responder};
+/// use tide::http::{Method, Request};
+/// use tide::StatusCode;
+/// # #[derive(serde::Deserialize)]
+/// # struct Login { user: String }
+///
+/// async fn login(mut req: tide::Request<()>) -> tide::Result<impl Responder> {
+///     let login: Login = body_form(&mut req).await?;
+///     Ok(format!("Hello {}", login.user))
+/// }
+///
+/// # async_std::task::block_on(async {
+/// let mut app = tide::new();
+/// app.at("/login").post(hightide::wrap(login));
+///
+/// let mut req = Request::new(Method::Post, "http://localhost/login");
+/// req.set_body("user=steve");
+/// req.set_content_type(tide::http::mime::FORM);
+/// let mut resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp.body_string().await.unwrap(), "Hello steve");
+///
+/// let mut req = Request::new(Method::Post, "http://localhost/login");
+/// req.set_body(r#"{"user": "steve"}"#);
+/// req.set_content_type(tide::http::mime::JSON);
+/// let resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp.status(), StatusCode::BadRequest);
+/// # });
+/// ```
+pub async fn body_form<T, State>(req: &mut Request<State>) -> tide::Result<T>
+where
+    T: DeserializeOwned,
+{
+    if let Some(mime) = req.content_type() {
+        if mime.essence() != mime::FORM.essence() {
+            return Err(tide::Error::from_str(
+                StatusCode::BadRequest,
+                format!("expected a form body, got {}", mime.essence()),
+            ));
+        }
+    }
+
+    let bytes = req.body_bytes().await?;
+    serde_urlencoded::from_bytes(&bytes).map_err(|err| {
+        tide::Error::from_str(
+            StatusCode::BadRequest,
+            format!("invalid form body: {}", err),
+        )
+    })
+}
+
+/// Parse the query string of the request
+///
+/// A request without a query string is parsed the same as an empty one, so it succeeds if all
+/// the fields are optional. A parameter given without a value (such as `?name=`) is an empty
+/// string. Query strings that fail to parse are a Bad Request (400) error.
+/// ```
+/// use hightide::query;
+/// use tide::http::{Method, Request};
+/// use tide::StatusCode;
+/// # #[derive(serde::Deserialize)]
+/// # struct Page { page: Option<u32>, sort: Option<String> }
+///
+/// # async_std::task::block_on(async {
+/// let mut app = tide::new();
+/// app.at("/").get(|req: tide::Request<()>| async move {
+///     let page: Page = query(&req)?;
+///     Ok(format!("{:?} {:?}", page.page, page.sort))
+/// });
+///
+/// let req = Request::new(Method::Get, "http://localhost/?page=2&sort=");
+/// let mut resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp.body_string().await.unwrap(), "Some(2) Some(\"\")");
+///
+/// let req = Request::new(Method::Get, "http://localhost/");
+/// let mut resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp.body_string().await.unwrap(), "None None");
+///
+/// let req = Request::new(Method::Get, "http://localhost/?page=two");
+/// let resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp.status(), StatusCode::BadRequest);
+/// # });
+/// ```
+pub fn query<T, State>(req: &Request<State>) -> tide::Result<T>
+where
+    T: DeserializeOwned,
+{
+    let query = req.url().query().unwrap_or("");
+    serde_urlencoded::from_str(query).map_err(|err| {
+        tide::Error::from_str(
+            StatusCode::BadRequest,
+            format!("invalid query string: {}", err),
+        )
+    })
+}
+
+/// An extension trait for reading typed headers (from the `hyperx` crate) from a request
+///
+/// This is implemented for both `tide::Request` and `tide::http::Request`, the latter is what
+/// `Responder::into_response_for` receives.
+/// ```
+/// use hightide::RequestExt;
+/// use hyperx::header::ContentLength;
+/// use tide::http::{Method, Request};
+/// use tide::StatusCode;
+///
+/// let mut req = Request::new(Method::Get, "http://example.com/");
+/// assert!(req.typed_header::<ContentLength>().unwrap().is_none());
+///
+/// req.insert_header("Content-Length", "42");
+/// assert_eq!(req.typed_header::<ContentLength>().unwrap(), Some(ContentLength(42)));
+///
+/// req.insert_header("Content-Length", "lots");
+/// let err = req.typed_header::<ContentLength>().unwrap_err();
+/// assert_eq!(err.status(), StatusCode::BadRequest);
+/// ```
+pub trait RequestExt {
+    /// Read and parse a typed header
+    ///
+    /// Returns `Ok(None)` if the header is absent, and a Bad Request (400) error if the header
+    /// is present but can't be parsed.
+    fn typed_header<H: Header>(&self) -> tide::Result<Option<H>>;
+
+    /// Pick the language from `available` that best matches the `Accept-Language` header
+    ///
+    /// Languages are tried in the order of the client's quality values, and ranges with
+    /// `q=0` are skipped. A range matches a language exactly (ignoring case) or by prefix, so
+    /// `en` matches `en-US` and `en-GB` matches `en`, an exact match is preferred and otherwise
+    /// the first match in `available` is used. `*` matches the first available language which
+    /// isn't excluded by a `q=0` range.
+    ///
+    /// Without an `Accept-Language` header the client accepts anything, so the first available
+    /// language is returned. If nothing acceptable is available this returns `None`, and you
+    /// can fall back to a default or respond with Not Acceptable (406). Add `Vary:
+    /// Accept-Language` to responses which depend on this.
+    /// ```
+    /// use hightide::RequestExt;
+    /// use tide::http::{Method, Request};
+    ///
+    /// let mut req = Request::new(Method::Get, "http://example.com/");
+    /// assert_eq!(req.preferred_language(&["en", "fr"]), Some("en"));
+    ///
+    /// req.insert_header("Accept-Language", "de;q=0.9, fr-CA, en;q=0.5");
+    /// assert_eq!(req.preferred_language(&["en", "fr"]), Some("fr"));
+    /// assert_eq!(req.preferred_language(&["de-DE", "en"]), Some("de-DE"));
+    /// assert_eq!(req.preferred_language(&["ja"]), None);
+    ///
+    /// req.insert_header("Accept-Language", "*, en;q=0");
+    /// assert_eq!(req.preferred_language(&["en", "fr"]), Some("fr"));
+    /// assert_eq!(req.preferred_language(&["en-US"]), None);
+    /// ```
+    fn preferred_language<'a>(&self, available: &[&'a str]) -> Option<&'a str>;
+}
+
+impl<State> RequestExt for Request<State> {
+    fn typed_header<H: Header>(&self) -> tide::Result<Option<H>> {
+        parse_typed_header(self.as_ref())
+    }
+
+    fn preferred_language<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        accept_language(self.as_ref(), available)
+    }
+}
+
+impl RequestExt for http::Request {
+    fn typed_header<H: Header>(&self) -> tide::Result<Option<H>> {
+        parse_typed_header(self.as_ref())
+    }
+
+    fn preferred_language<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        accept_language(self.as_ref(), available)
+    }
+}
+
+fn accept_language<'a>(headers: &Headers, available: &[&'a str]) -> Option<&'a str> {
+    let header = headers.get("Accept-Language").map(|values| {
+        values
+            .iter()
+            .map(|v| v.as_str())
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+    preferred_language(header.as_deref(), available)
+}
+
+fn parse_typed_header<H: Header>(headers: &Headers) -> tide::Result<Option<H>> {
+    let values = match headers.get(H::header_name()) {
+        Some(values) => values,
+        None => return Ok(None),
+    };
+
+    let raw: Raw = values
+        .iter()
+        .map(|value| value.as_str().as_bytes().to_vec())
+        .collect::<Vec<_>>()
+        .into();
+    H::parse_header(&raw)
+        .map(Some)
+        .map_err(|err| tide::Error::new(StatusCode::BadRequest, err))
+}
@@ -0,0 +1,140 @@
+use crate::{wrap, High};
+use async_trait::async_trait;
+use std::collections::hash_map::RandomState;
+use std::fmt::Write;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tide::http::headers::HeaderName;
+use tide::{Endpoint, Request};
+
+/// The id of the current request, stored in the request extensions by `wrap_request_id` (or
+/// `EndpointExt::with_request_id`)
+/// ```
+/// use hightide::RequestId;
+/// use tide::Request;
+///
+/// async fn handler(req: Request<()>) -> String {
+///     let id = req.ext::<RequestId>().unwrap();
+///     format!("handling request {}", id.0)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// Propagates request ids through an endpoint, see `wrap_request_id`
+pub struct HighRequestId<E> {
+    header: HeaderName,
+    inner: E,
+}
+
+/// Wrap an endpoint to allow it to return the Responder types, echoing the request id onto the
+/// response
+///
+/// The id is read from the `X-Request-Id` header of the request, or a random UUID (version 4) is
+/// generated if the header is missing. The id is stored in the request extensions as a
+/// `RequestId` so the endpoint can log it, and is set in the same header on the response. Use
+/// `header_name` to use a different header.
+///
+/// Errors from the endpoint are turned into responses (keeping the status) so that they get the
+/// header too. This means an outer `with_errors` no longer sees them as errors, so apply
+/// `with_errors` before `with_request_id`.
+/// ```
+/// use hightide::wrap_request_id;
+/// use tide::http::{Method, Request};
+///
+/// # async_std::task::block_on(async {
+/// let mut app = tide::new();
+/// app.at("/").get(wrap_request_id(|_| async { "hello" }));
+/// app.at("/fail").get(wrap_request_id(|_| async {
+///     Err::<&str, _>(tide::Error::from_str(tide::StatusCode::BadRequest, "bad input"))
+/// }));
+///
+/// let mut req = Request::new(Method::Get, "http://localhost/");
+/// req.insert_header("X-Request-Id", "abc-123");
+/// let resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp["X-Request-Id"], "abc-123");
+///
+/// let req = Request::new(Method::Get, "http://localhost/");
+/// let resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp["X-Request-Id"].as_str().len(), 36);
+///
+/// let mut req = Request::new(Method::Get, "http://localhost/fail");
+/// req.insert_header("X-Request-Id", "abc-456");
+/// let resp: tide::http::Response = app.respond(req).await.unwrap();
+/// assert_eq!(resp.status(), tide::StatusCode::BadRequest);
+/// assert_eq!(resp["X-Request-Id"], "abc-456");
+/// # });
+/// ```
+pub fn wrap_request_id<F>(f: F) -> HighRequestId<High<F>> {
+    HighRequestId::new(wrap(f))
+}
+
+impl<E> HighRequestId<E> {
+    pub(crate) fn new(inner: E) -> Self {
+        Self {
+            header: HeaderName::from("X-Request-Id"),
+            inner,
+        }
+    }
+
+    /// Read and write the id using a different header, such as `X-Correlation-Id`
+    pub fn header_name(mut self, name: impl Into<HeaderName>) -> Self {
+        self.header = name.into();
+        self
+    }
+}
+
+#[async_trait]
+impl<State, E> Endpoint<State> for HighRequestId<E>
+where
+    State: Clone + Send + Sync + 'static,
+    E: Endpoint<State>,
+{
+    async fn call(&self, mut req: Request<State>) -> tide::Result<tide::Response> {
+        let id = match req.header(&self.header) {
+            Some(values) => values.last().as_str().to_owned(),
+            None => generate_id(),
+        };
+        req.set_ext(RequestId(id.clone()));
+
+        let mut resp = match self.inner.call(req).await {
+            Ok(resp) => resp,
+            Err(err) => tide::Response::from(err),
+        };
+        resp.insert_header(self.header.clone(), id);
+        Ok(resp)
+    }
+}
+
+/// Generate a random version 4 UUID
+fn generate_id() -> String {
+    let mut bytes = random_bytes();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let mut id = String::with_capacity(36);
+    for (i, b) in bytes.iter().enumerate() {
+        if matches!(i, 4 | 6 | 8 | 10) {
+            id.push('-');
+        }
+        write!(id, "{:02x}", b).unwrap();
+    }
+    id
+}
+
+/// Generate 16 random bytes, using the randomly seeded std hasher so no extra dependency is
+/// needed. This is unique enough for ids but is not cryptographically secure.
+pub(crate) fn random_bytes() -> [u8; 16] {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let state = RandomState::new();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let mut bytes = [0u8; 16];
+    for (i, chunk) in bytes.chunks_mut(8).enumerate() {
+        let mut hasher = state.build_hasher();
+        hasher.write_u64(count);
+        hasher.write_usize(i);
+        chunk.copy_from_slice(&hasher.finish().to_be_bytes());
+    }
+    bytes
+}
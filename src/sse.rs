@@ -0,0 +1,196 @@
+use crate::stream::body_from_stream;
+use crate::Responder;
+use futures::{Future, Stream, StreamExt};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tide::http::{headers, mime};
+
+/// A single server-sent event
+///
+/// Multi-line data is sent as multiple `data:` lines, which the client joins back together.
+/// Line breaks in the event name and id are not allowed by the format and are removed.
+#[derive(Debug, Clone, Default)]
+pub struct SseEvent {
+    event: Option<String>,
+    id: Option<String>,
+    data: String,
+}
+
+impl SseEvent {
+    /// Create an empty event
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the event name (`event:` field), if not set the client treats it as a `message` event
+    pub fn event(mut self, name: impl Into<String>) -> Self {
+        self.event = Some(name.into());
+        self
+    }
+
+    /// Set the event id (`id:` field)
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Set the event payload (`data:` field)
+    pub fn data(mut self, payload: impl Into<String>) -> Self {
+        self.data = payload.into();
+        self
+    }
+
+    fn to_frame(&self) -> String {
+        let mut frame = String::new();
+        if let Some(event) = &self.event {
+            push_field(&mut frame, "event", &single_line(event));
+        }
+        if let Some(id) = &self.id {
+            push_field(&mut frame, "id", &single_line(id));
+        }
+        for line in self.data.lines() {
+            push_field(&mut frame, "data", line);
+        }
+        if self.data.is_empty() {
+            push_field(&mut frame, "data", "");
+        }
+        frame.push('\n');
+        frame
+    }
+}
+
+fn single_line(s: &str) -> String {
+    s.replace(['\r', '\n'], "")
+}
+
+fn push_field(frame: &mut String, name: &str, value: &str) {
+    frame.push_str(name);
+    frame.push_str(": ");
+    frame.push_str(value);
+    frame.push('\n');
+}
+
+/// A server-sent events response, streaming events to the client as they are produced
+///
+/// The response has content type `text/event-stream` and `Cache-Control: no-cache`. The body is
+/// streamed, so the connection stays open until the stream ends. By default nothing is sent
+/// between events, see `keep_alive` to send heartbeats.
+/// ```
+/// use futures::stream;
+/// use hightide::{Responder, Sse, SseEvent};
+///
+/// # async_std::task::block_on(async {
+/// let events = stream::iter(vec![
+///     SseEvent::new().event("update").id("1").data("first"),
+///     SseEvent::new().data("second\nline"),
+/// ]);
+/// let mut resp = Sse::new(events).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "text/event-stream");
+/// assert_eq!(resp["Cache-Control"], "no-cache");
+///
+/// let body = resp.take_body().into_string().await.unwrap();
+/// assert_eq!(body, "event: update\nid: 1\ndata: first\n\ndata: second\ndata: line\n\n");
+/// # });
+/// ```
+pub struct Sse<S> {
+    stream: S,
+    keep_alive: Option<Duration>,
+}
+
+impl<S> Sse<S>
+where
+    S: Stream<Item = SseEvent> + Send + Sync + 'static,
+{
+    /// Create the response from a stream of events
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            keep_alive: None,
+        }
+    }
+
+    /// Send a comment frame (`: keepalive` followed by a blank line) whenever no event has been
+    /// sent for `interval`, so idle proxies don't close the connection. Clients ignore comments.
+    /// ```
+    /// use futures::{stream, AsyncReadExt};
+    /// use hightide::{Responder, Sse, SseEvent};
+    /// use std::time::Duration;
+    ///
+    /// # async_std::task::block_on(async {
+    /// // a stream with no events, so only keep-alives are sent
+    /// let events = stream::pending::<SseEvent>();
+    /// let sse = Sse::new(events).keep_alive(Duration::from_millis(20));
+    /// let mut body = sse.into_response().unwrap().take_body();
+    ///
+    /// let mut frames = [0u8; 26];
+    /// body.read_exact(&mut frames).await.unwrap();
+    /// assert_eq!(&frames[..], &b": keepalive\n\n: keepalive\n\n"[..]);
+    /// # });
+    /// ```
+    pub fn keep_alive(mut self, interval: Duration) -> Self {
+        self.keep_alive = Some(interval);
+        self
+    }
+}
+
+impl<S> Responder for Sse<S>
+where
+    S: Stream<Item = SseEvent> + Send + Sync + 'static,
+{
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let mut body = match self.keep_alive {
+            Some(interval) => body_from_stream(KeepAlive::new(self.stream, interval)),
+            None => body_from_stream(
+                self.stream
+                    .map(|event| Ok::<_, io::Error>(event.to_frame())),
+            ),
+        };
+        body.set_mime(mime::SSE);
+
+        let mut resp = tide::Response::new(tide::StatusCode::Ok);
+        resp.set_body(body);
+        resp.insert_header(headers::CACHE_CONTROL, "no-cache");
+        Ok(resp)
+    }
+}
+
+type Timer = Pin<Box<dyn Future<Output = ()> + Send + Sync>>;
+
+fn timer(interval: Duration) -> Timer {
+    Box::pin(async_std::task::sleep(interval))
+}
+
+/// Event frames from the stream, with a keep-alive comment whenever the stream has been idle for
+/// the interval. Ends when the event stream ends.
+struct KeepAlive<S> {
+    events: Pin<Box<S>>,
+    interval: Duration,
+    timer: Timer,
+}
+
+impl<S: Stream<Item = SseEvent>> KeepAlive<S> {
+    fn new(events: S, interval: Duration) -> Self {
+        Self {
+            events: Box::pin(events),
+            interval,
+            timer: timer(interval),
+        }
+    }
+}
+
+impl<S: Stream<Item = SseEvent>> Stream for KeepAlive<S> {
+    type Item = io::Result<String>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Poll::Ready(event) = self.events.as_mut().poll_next(cx) {
+            self.timer = timer(self.interval);
+            return Poll::Ready(event.map(|event| Ok(event.to_frame())));
+        }
+
+        futures::ready!(self.timer.as_mut().poll(cx));
+        self.timer = timer(self.interval);
+        Poll::Ready(Some(Ok(": keepalive\n\n".to_owned())))
+    }
+}
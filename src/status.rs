@@ -0,0 +1,43 @@
+//! Zero-body responders for common status codes
+//!
+//! These read more clearly in handler code than returning the bare `StatusCode`.
+//! ```
+//! use hightide::status::Accepted;
+//! use hightide::Responder;
+//! use tide::{Request, StatusCode};
+//!
+//! async fn enqueue_job(_: Request<()>) -> tide::Result<Accepted> {
+//!     // ... queue the job to run later
+//!     Ok(Accepted)
+//! }
+//!
+//! assert_eq!(Accepted.into_response().unwrap().status(), StatusCode::Accepted);
+//! ```
+
+use crate::Responder;
+use tide::StatusCode;
+
+macro_rules! status_responder {
+    ($(#[$doc:meta] $name:ident),*) => {
+        $(
+            #[$doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub struct $name;
+
+            impl Responder for $name {
+                fn into_response(self) -> tide::Result<tide::Response> {
+                    StatusCode::$name.into_response()
+                }
+            }
+        )*
+    };
+}
+
+status_responder!(
+    /// An empty response with status code Accepted (202)
+    Accepted,
+    /// An empty response with status code No Content (204)
+    NoContent,
+    /// An empty response with status code Reset Content (205)
+    ResetContent
+);
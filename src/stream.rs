@@ -0,0 +1,264 @@
+use crate::Responder;
+use futures::io::{AsyncBufRead, BufReader};
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
+use std::io;
+use tide::convert::Serialize;
+use tide::http::{mime, Mime};
+use tide::Body;
+
+/// Create a streaming body with unknown length from a stream of chunks
+pub(crate) fn body_from_stream<S, B>(chunks: S) -> Body
+where
+    S: Stream<Item = io::Result<B>> + Send + Sync + 'static,
+    B: AsRef<[u8]> + Send + Sync + 'static,
+{
+    let reader = BufReader::new(Box::pin(chunks).into_async_read());
+    Body::from_reader(reader, None)
+}
+
+/// A streaming response body made of byte chunks, sent with chunked transfer encoding
+///
+/// The chunks can be anything which is `AsRef<[u8]>`, such as `Vec<u8>` or `bytes::Bytes`. The
+/// content type defaults to `application/octet-stream`. If the stream yields an error then the
+/// body stops with that error, so the connection is closed rather than sending a truncated body
+/// as if it were complete.
+/// ```
+/// use futures::stream;
+/// use hightide::{Responder, StreamBody};
+/// use std::io;
+///
+/// # async_std::task::block_on(async {
+/// let chunks = stream::iter(vec![Ok(b"hello ".to_vec()), Ok(b"world".to_vec())]);
+/// let mut resp = StreamBody::new(chunks).content_type("text/plain").into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "text/plain");
+/// assert!(resp.len().is_none());
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "hello world");
+///
+/// let chunks = stream::iter(vec![
+///     Ok(b"partial".to_vec()),
+///     Err(io::Error::new(io::ErrorKind::Other, "upstream closed")),
+/// ]);
+/// let mut resp = StreamBody::new(chunks).into_response().unwrap();
+/// assert!(resp.take_body().into_bytes().await.is_err());
+/// # });
+/// ```
+pub struct StreamBody<S> {
+    stream: S,
+    mime: Mime,
+}
+
+impl<S, B> StreamBody<S>
+where
+    S: Stream<Item = io::Result<B>> + Send + Sync + 'static,
+    B: AsRef<[u8]> + Send + Sync + 'static,
+{
+    /// Create the response from a stream of chunks
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            mime: mime::BYTE_STREAM,
+        }
+    }
+
+    /// Set the content type of the body
+    pub fn content_type(mut self, mime: impl Into<Mime>) -> Self {
+        self.mime = mime.into();
+        self
+    }
+}
+
+impl<S, B> Responder for StreamBody<S>
+where
+    S: Stream<Item = io::Result<B>> + Send + Sync + 'static,
+    B: AsRef<[u8]> + Send + Sync + 'static,
+{
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let mut body = body_from_stream(self.stream);
+        body.set_mime(self.mime);
+        Ok(tide::Response::from(body))
+    }
+}
+
+/// A response body streamed from any async reader, such as a file or a decrypting adapter
+///
+/// The reader uses the `futures::io` traits, so it isn't tied to an async runtime. Wrap plain
+/// `AsyncRead` types in `futures::io::BufReader`. The content type defaults to
+/// `application/octet-stream`. If the length isn't given the body is sent with chunked transfer
+/// encoding, otherwise it is sent as `Content-Length` and the reader should produce exactly that
+/// many bytes.
+/// ```
+/// use futures::io::Cursor;
+/// use hightide::{ReaderBody, Responder};
+///
+/// # async_std::task::block_on(async {
+/// let reader = Cursor::new(b"<svg/>".to_vec());
+/// let mut resp = ReaderBody::new(reader)
+///     .content_type("image/svg+xml")
+///     .length(6)
+///     .into_response()
+///     .unwrap();
+/// assert_eq!(resp["Content-Type"], "image/svg+xml");
+/// assert_eq!(resp.len(), Some(6));
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "<svg/>");
+/// # });
+/// ```
+pub struct ReaderBody<R> {
+    reader: R,
+    mime: Mime,
+    length: Option<usize>,
+}
+
+impl<R> ReaderBody<R>
+where
+    R: AsyncBufRead + Unpin + Send + Sync + 'static,
+{
+    /// Create the response from a reader, with unknown length
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            mime: mime::BYTE_STREAM,
+            length: None,
+        }
+    }
+
+    /// Set the content type of the body
+    pub fn content_type(mut self, mime: impl Into<Mime>) -> Self {
+        self.mime = mime.into();
+        self
+    }
+
+    /// Set the length of the body in bytes
+    pub fn length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self
+    }
+}
+
+impl<R> Responder for ReaderBody<R>
+where
+    R: AsyncBufRead + Unpin + Send + Sync + 'static,
+{
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let mut body = Body::from_reader(self.reader, self.length);
+        body.set_mime(self.mime);
+        Ok(tide::Response::from(body))
+    }
+}
+
+/// A streaming JSON array response, serializing each item as it is produced
+///
+/// Unlike `Json` the whole array is never held in memory, which keeps memory use flat for large
+/// exports. An empty stream produces `[]`. If an item fails to serialize the body stops with an
+/// error, so the connection is closed rather than sending invalid JSON.
+/// ```
+/// use futures::stream;
+/// use hightide::{JsonStream, Responder};
+///
+/// # async_std::task::block_on(async {
+/// let mut resp = JsonStream::new(stream::iter(vec![1, 2, 3])).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "application/json");
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "[1,2,3]");
+///
+/// let mut resp = JsonStream::new(stream::iter(Vec::<u32>::new())).into_response().unwrap();
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "[]");
+/// # });
+/// ```
+pub struct JsonStream<S> {
+    stream: S,
+}
+
+impl<S, T> JsonStream<S>
+where
+    S: Stream<Item = T> + Send + Sync + 'static,
+    T: Serialize,
+{
+    /// Create the response from a stream of items
+    pub fn new(stream: S) -> Self {
+        Self { stream }
+    }
+}
+
+impl<S, T> Responder for JsonStream<S>
+where
+    S: Stream<Item = T> + Send + Sync + 'static,
+    T: Serialize,
+{
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let items = self.stream.enumerate().map(|(i, item)| {
+            let mut chunk = if i == 0 { Vec::new() } else { vec![b','] };
+            serde_json::to_writer(&mut chunk, &item)?;
+            Ok::<_, io::Error>(chunk)
+        });
+        let open = stream::once(future::ready(Ok(b"[".to_vec())));
+        let close = stream::once(future::ready(Ok(b"]".to_vec())));
+
+        let mut body = body_from_stream(open.chain(items).chain(close));
+        body.set_mime(mime::JSON);
+        Ok(tide::Response::from(body))
+    }
+}
+
+/// A streaming newline delimited JSON (NDJSON) response body, with content type
+/// `application/x-ndjson`
+///
+/// Each item is serialized as one line of JSON followed by `\n`, so clients can parse the
+/// response as it arrives. If an item fails to serialize the body stops with the error, so the
+/// connection is closed rather than skipping the item.
+/// ```
+/// use futures::stream;
+/// use hightide::{JsonLines, Responder};
+///
+/// # async_std::task::block_on(async {
+/// let mut resp = JsonLines::new(stream::iter(vec![1, 2, 3])).into_response().unwrap();
+/// assert_eq!(resp["Content-Type"], "application/x-ndjson");
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "1\n2\n3\n");
+///
+/// let mut resp = JsonLines::iter(vec!["a", "b"]).into_response().unwrap();
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "\"a\"\n\"b\"\n");
+/// # });
+/// ```
+pub struct JsonLines<S> {
+    stream: S,
+}
+
+impl<S, T> JsonLines<S>
+where
+    S: Stream<Item = T> + Send + Sync + 'static,
+    T: Serialize,
+{
+    /// Create the response from a stream of items
+    pub fn new(stream: S) -> Self {
+        Self { stream }
+    }
+}
+
+impl<I> JsonLines<stream::Iter<I>>
+where
+    I: Iterator + Send + Sync + 'static,
+    I::Item: Serialize,
+{
+    /// Create the response from an iterator of items
+    pub fn iter(items: impl IntoIterator<IntoIter = I>) -> Self {
+        Self {
+            stream: stream::iter(items),
+        }
+    }
+}
+
+impl<S, T> Responder for JsonLines<S>
+where
+    S: Stream<Item = T> + Send + Sync + 'static,
+    T: Serialize,
+{
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let lines = self.stream.map(|item| {
+            let mut line = serde_json::to_vec(&item)?;
+            line.push(b'\n');
+            Ok::<_, io::Error>(line)
+        });
+
+        let mut body = body_from_stream(lines);
+        body.set_mime(Mime::from("application/x-ndjson"));
+        Ok(tide::Response::from(body))
+    }
+}
@@ -0,0 +1,88 @@
+#[cfg(feature = "askama")]
+use crate::Responder;
+#[cfg(feature = "tera")]
+use crate::Response;
+#[cfg(feature = "tera")]
+use tide::http::mime;
+use tide::StatusCode;
+
+#[cfg(feature = "tera")]
+impl Response {
+    /// Render a Tera template as the body of the response, with content type `text/html`
+    /// (requires the `tera` feature)
+    ///
+    /// The `Tera` instance is yours to manage, usually it is loaded once and kept in the app
+    /// state. Render errors are returned as Internal Server Error (500).
+    /// ```
+    /// use hightide::Response;
+    /// use tera::{Context, Tera};
+    ///
+    /// let mut tera = Tera::default();
+    /// tera.add_raw_template("hello.html", "<p>Hello {{ name }}</p>").unwrap();
+    ///
+    /// let mut ctx = Context::new();
+    /// ctx.insert("name", "world");
+    ///
+    /// # async_std::task::block_on(async {
+    /// let mut resp = Response::ok().render(&tera, "hello.html", &ctx).unwrap().into_inner();
+    /// assert_eq!(resp.content_type(), Some(tide::http::mime::HTML));
+    /// assert_eq!(resp.take_body().into_string().await.unwrap(), "<p>Hello world</p>");
+    ///
+    /// let err = Response::ok().render(&tera, "missing.html", &ctx).err().unwrap();
+    /// assert_eq!(err.status(), tide::StatusCode::InternalServerError);
+    /// # });
+    /// ```
+    pub fn render(
+        mut self,
+        tera: &tera::Tera,
+        name: &str,
+        ctx: &tera::Context,
+    ) -> tide::Result<Self> {
+        let body = tera
+            .render(name, ctx)
+            .map_err(|err| tide::Error::new(StatusCode::InternalServerError, err))?;
+        self.inner.set_body(body);
+        self.inner.set_content_type(mime::HTML);
+        Ok(self)
+    }
+}
+
+/// A responder for an Askama template (requires the `askama` feature)
+///
+/// The template is rendered and the content type is set from the template's `MIME_TYPE`. Render
+/// errors are returned as Internal Server Error (500).
+///
+/// `Responder` can't be implemented directly for every `askama::Template` as it would conflict
+/// with the other implementations, so templates are wrapped instead.
+/// ```
+/// use askama::Template;
+/// use hightide::{Askama, Responder};
+///
+/// #[derive(Template)]
+/// #[template(source = "<p>Hello {{ name }}</p>", ext = "html")]
+/// struct Hello<'a> {
+///     name: &'a str,
+/// }
+///
+/// # async_std::task::block_on(async {
+/// let mut resp = Askama(Hello { name: "world" }).into_response().unwrap();
+/// assert_eq!(resp.content_type().unwrap().essence(), "text/html");
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "<p>Hello world</p>");
+/// # });
+/// ```
+#[cfg(feature = "askama")]
+pub struct Askama<T: askama::Template>(pub T);
+
+#[cfg(feature = "askama")]
+impl<T: askama::Template> Responder for Askama<T> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let body = self
+            .0
+            .render()
+            .map_err(|err| tide::Error::new(StatusCode::InternalServerError, err))?;
+        let mut resp = tide::Response::new(StatusCode::Ok);
+        resp.set_body(body);
+        resp.set_content_type(T::MIME_TYPE);
+        Ok(resp)
+    }
+}
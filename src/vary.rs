@@ -0,0 +1,48 @@
+use crate::Response;
+use tide::http::headers;
+
+impl Response {
+    /// Add header names to the `Vary` header, telling caches which request headers the response
+    /// depends on
+    ///
+    /// Names are added to any already in the header, and names which are already present
+    /// (compared case-insensitively) are skipped.
+    /// ```
+    /// use hightide::Response;
+    ///
+    /// let resp = Response::ok()
+    ///     .vary(&["Accept"])
+    ///     .vary(&["Accept-Encoding", "accept"])
+    ///     .into_inner();
+    /// assert_eq!(resp["Vary"], "Accept, Accept-Encoding");
+    /// ```
+    pub fn vary(mut self, headers: &[&str]) -> Self {
+        append_vary(&mut self.inner, headers);
+        self
+    }
+}
+
+/// Add header names to the `Vary` header of a response, skipping names already present
+pub(crate) fn append_vary(resp: &mut tide::Response, names: &[&str]) {
+    let mut vary: Vec<String> = resp
+        .header(headers::VARY)
+        .map(|values| {
+            values
+                .iter()
+                .flat_map(|value| value.as_str().split(','))
+                .map(|name| name.trim().to_owned())
+                .filter(|name| !name.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    for name in names {
+        if !vary.iter().any(|v| v.eq_ignore_ascii_case(name)) {
+            vary.push((*name).to_owned());
+        }
+    }
+
+    if !vary.is_empty() {
+        resp.insert_header(headers::VARY, vary.join(", "));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{checked_header_value, Responder};
+use tide::http::{self, headers::HeaderName};
+
+/// A wrapper which adds headers to the response of any responder, created with
+/// `Responder::with_header`
+///
+/// The headers are inserted after the inner response is built, so they replace any headers of
+/// the same name. An invalid header value, including one containing control characters such as
+/// CR or LF, is returned as an error from `into_response`.
+/// ```
+/// use hightide::{Json, Responder};
+///
+/// let resp = Json(vec![1, 2, 3])
+///     .with_header("X-Total-Count", "3")
+///     .with_header("X-Page", "1")
+///     .into_response()
+///     .unwrap();
+/// assert_eq!(resp["X-Total-Count"], "3");
+/// assert_eq!(resp["X-Page"], "1");
+/// assert_eq!(resp.content_type(), Some(tide::http::mime::JSON));
+///
+/// assert!("body".with_header("X-Bad", "caf\u{e9}").into_response().is_err());
+///
+/// let err = "body"
+///     .with_header("X-Bad", "a\r\nSet-Cookie: evil=1")
+///     .into_response()
+///     .unwrap_err();
+/// assert_eq!(err.status(), tide::StatusCode::InternalServerError);
+/// ```
+pub struct WithHeaders<R: Responder> {
+    inner: R,
+    headers: Vec<(HeaderName, String)>,
+}
+
+impl<R: Responder> WithHeaders<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Add another header
+    pub fn with_header(mut self, name: impl Into<HeaderName>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+impl<R: Responder> Responder for WithHeaders<R> {
+    fn into_response(self) -> tide::Result<tide::Response> {
+        let resp = self.inner.into_response()?;
+        apply_headers(self.headers, resp)
+    }
+
+    fn into_response_for(self, req: &http::Request) -> tide::Result<tide::Response> {
+        let resp = self.inner.into_response_for(req)?;
+        apply_headers(self.headers, resp)
+    }
+}
+
+fn apply_headers(
+    headers: Vec<(HeaderName, String)>,
+    mut resp: tide::Response,
+) -> tide::Result<tide::Response> {
+    for (name, value) in headers {
+        let value = checked_header_value(&value)?;
+        resp.insert_header(name, value);
+    }
+    Ok(resp)
+}